@@ -0,0 +1,124 @@
+use ratatecs::prelude::*;
+
+fn main() {
+    App::new_tui().add_plugins(counters::panel).run();
+}
+
+mod counters {
+    use ratatecs::prelude::*;
+    use ratatui::widgets::Paragraph;
+    use symbols::border;
+
+    // Each counter is an entity, rendered in its own column
+    #[derive(Component)]
+    struct Counter {
+        value: u32,
+        column: u16,
+    }
+
+    #[derive(Component)]
+    struct Selected;
+
+    pub fn panel(app: &mut App) {
+        app.add_systems(Startup, spawn_counters);
+
+        app.add_systems(Update, (exit_on_esc, change_selection, change_counter));
+        app.add_systems(PostUpdate, render);
+    }
+
+    fn spawn_counters(mut commands: Commands) {
+        commands.spawn((
+            Counter {
+                value: 0,
+                column: 0,
+            },
+            Selected,
+        ));
+        commands.spawn(Counter {
+            value: 0,
+            column: 1,
+        });
+        commands.spawn(Counter {
+            value: 0,
+            column: 2,
+        });
+    }
+
+    fn exit_on_esc(mut keys: EventReader<KeyPressed>, mut exit: EventWriter<AppExit>) {
+        if keys.read().any(|key| key.0.code == event::KeyCode::Esc) {
+            exit.send(AppExit::Success);
+        }
+    }
+
+    fn change_selection(
+        mut commands: Commands,
+        mut keys: EventReader<KeyPressed>,
+        counters: Query<(Entity, &Counter, Has<Selected>)>,
+    ) {
+        if !keys.read().any(|key| key.0.code == event::KeyCode::Tab) {
+            return;
+        }
+        let count = counters.iter().count() as u16;
+        let Some(selected) = counters
+            .iter()
+            .find_map(|(_, counter, selected)| selected.then_some(counter.column))
+        else {
+            return;
+        };
+        for (entity, counter, _) in &counters {
+            if counter.column == (selected + 1) % count {
+                commands.entity(entity).insert(Selected);
+            } else {
+                commands.entity(entity).remove::<Selected>();
+            }
+        }
+    }
+
+    fn change_counter(
+        mut keys: EventReader<KeyPressed>,
+        mut counters: Query<&mut Counter, With<Selected>>,
+    ) {
+        for KeyPressed(key_event) in keys.read() {
+            for mut counter in &mut counters {
+                match key_event.code {
+                    event::KeyCode::Left => counter.value = counter.value.saturating_sub(1),
+                    event::KeyCode::Right => counter.value += 1,
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    fn render(mut drawer: WidgetDrawer, counters: Query<(&Counter, Has<Selected>)>) {
+        let area = drawer.area();
+        let columns = Layout::horizontal([Constraint::Fill(1); 3]).split(area);
+
+        for (counter, selected) in &counters {
+            let instructions = Line::from(vec![
+                " Select ".into(),
+                "<Tab>".blue().bold(),
+                " Change ".into(),
+                "<Left/Right>".blue().bold(),
+                " Quit ".into(),
+                "<Esc> ".blue().bold(),
+            ]);
+            let block = titled_block(Line::from(" Counter ".bold()), instructions).border_set(
+                if selected {
+                    border::THICK
+                } else {
+                    border::PLAIN
+                },
+            );
+            let text = Text::from(vec![Line::from(vec![
+                "Value: ".into(),
+                counter.value.to_string().yellow(),
+            ])]);
+
+            drawer.push_widget(
+                Box::new(Paragraph::new(text).centered().block(block)),
+                columns[counter.column as usize],
+                0,
+            );
+        }
+    }
+}
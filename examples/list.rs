@@ -0,0 +1,73 @@
+use ratatecs::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((RatatEcsPlugins::new(), app::panel))
+        .run();
+}
+
+mod app {
+    use ratatecs::prelude::*;
+    use ratatui::widgets::{List, ListState};
+
+    #[derive(Resource)]
+    struct Selection(WidgetStateId<ListState>);
+
+    pub fn panel(app: &mut App) {
+        app.add_systems(Startup, init_selection);
+
+        app.add_systems(Update, (exit_on_esc, change_selection));
+        app.add_systems(PostUpdate, render);
+    }
+
+    fn init_selection(mut commands: Commands, mut widgets: NonSendMut<WidgetsToDraw>) {
+        // The list state is kept across frames and updated during rendering
+        let state = widgets.add_widget_state(ListState::default().with_selected(Some(0)));
+        commands.insert_resource(Selection(state));
+    }
+
+    fn exit_on_esc(mut keys: EventReader<KeyPressed>, mut exit: EventWriter<AppExit>) {
+        if keys.read().any(|key| key.0.code == event::KeyCode::Esc) {
+            exit.send(AppExit::Success);
+        }
+    }
+
+    fn change_selection(
+        selection: Res<Selection>,
+        widgets: NonSend<WidgetsToDraw>,
+        mut keys: EventReader<KeyPressed>,
+    ) {
+        let Some(mut state) = widgets.widget_state_mut(selection.0) else {
+            return;
+        };
+        for KeyPressed(key_event) in keys.read() {
+            match key_event.code {
+                event::KeyCode::Up => state.select_previous(),
+                event::KeyCode::Down => state.select_next(),
+                _ => (),
+            }
+        }
+    }
+
+    fn render(selection: Res<Selection>, mut drawer: WidgetDrawer) {
+        let area = drawer.area();
+
+        let title = Line::from(" Fruits ".bold());
+        let instructions = Line::from(vec![
+            " Previous ".into(),
+            "<Up>".blue().bold(),
+            " Next ".into(),
+            "<Down>".blue().bold(),
+            " Quit ".into(),
+            "<Esc> ".blue().bold(),
+        ]);
+        let block = titled_block(title, instructions);
+
+        let list = List::new(["Apple", "Banana", "Cherry", "Durian", "Elderberry"])
+            .block(block)
+            .highlight_style(Style::new().yellow().bold())
+            .highlight_symbol("> ");
+
+        drawer.push_stateful_widget(Box::new(list), selection.0, area, 0);
+    }
+}
@@ -3,39 +3,60 @@ use ratatecs::prelude::*;
 fn main() {
     App::new()
         .add_plugins((
-            RatatEcsPlugins,
-            app::panel,
-            counter::panel,
-            progress::panel,
-            popup::panel,
+            RatatEcsPlugins::new(),
+            InputMapPlugin::new(
+                InputMap::new()
+                    .bind(event::KeyCode::Esc, app::AppAction::Quit)
+                    .bind(KeyBinding::ctrl('c'), app::AppAction::Quit)
+                    .bind(' ', app::AppAction::TogglePopup),
+            ),
         ))
+        .add_panel(app::panel)
+        .add_panel(counter::panel)
+        .add_panel(progress::panel)
+        .add_panel(popup::panel)
+        .add_systems(Update, cycle_focus)
         .run();
 }
 
 mod app {
 
     use ratatecs::prelude::*;
-    use ratatui::widgets::Block;
-    use symbols::border;
+    use ratatui::layout::Flex;
+
+    /// Actions shared by the panels, bound to keys in `main`.
+    #[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum AppAction {
+        Quit,
+        TogglePopup,
+    }
 
     pub fn panel(app: &mut App) {
-        app.add_systems(Update, exit_on_esc);
+        // The counter and progress panels each take half of the terminal, and center their
+        // block in it
+        app.add_layout(
+            Layout::horizontal([Constraint::Fill(1); 2]),
+            ["counter_column", "progress_column"],
+        );
+        app.add_systems(Update, exit_on_quit);
         app.add_systems(PostUpdate, render);
     }
 
-    fn exit_on_esc(event: Res<BackendEvent>, mut exit: EventWriter<AppExit>) {
-        if let Some(event) = &event.0 {
-            if let event::Event::Key(key_event) = event {
-                if key_event.code == event::KeyCode::Esc {
-                    exit.send(AppExit::Success);
-                }
-            }
+    /// A block in the middle of a column, shrinking along with the terminal.
+    pub fn centered() -> Layout {
+        Layout::vertical([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .horizontal_margin(2)
+    }
+
+    fn exit_on_quit(mut actions: EventReader<AppAction>, mut exit: EventWriter<AppExit>) {
+        if actions.read().any(|action| *action == AppAction::Quit) {
+            exit.send(AppExit::Success);
         }
     }
 
     fn render(mut drawer: WidgetDrawer) {
-        let frame = drawer.get_frame();
-        let area = frame.area();
+        let area = drawer.area();
 
         let title = Line::from(" My Great TUI ".bold());
         let instructions = Line::from(vec![
@@ -43,11 +64,10 @@ mod app {
             "<Esc> ".blue().bold(),
             " Toggle Popup ".into(),
             "<Space> ".blue().bold(),
+            " Switch Panel ".into(),
+            "<Tab> ".blue().bold(),
         ]);
-        let block = Block::bordered()
-            .title(title.centered())
-            .title_bottom(instructions.centered())
-            .border_set(border::THICK);
+        let block = titled_block(title, instructions);
 
         drawer.push_widget(Box::new(block), area, 0);
     }
@@ -55,22 +75,26 @@ mod app {
 
 mod counter {
     use ratatecs::prelude::*;
-    use ratatui::widgets::{Block, Paragraph};
-    use symbols::border;
+    use ratatui::widgets::Paragraph;
 
     #[derive(Resource)]
     struct Counter(u32);
 
     pub fn panel(app: &mut App) {
         app.insert_resource(Counter(0));
+        app.add_focusable("counter");
+        app.add_layout_in("counter_column", crate::app::centered(), ["counter"]);
 
-        app.add_systems(Update, change_counter);
+        app.add_systems(Update, change_counter.run_if(has_focus("counter")));
         app.add_systems(PostUpdate, render);
     }
 
-    fn change_counter(mut counter: ResMut<Counter>, event: Res<BackendEvent>) {
-        if let Some(event) = &event.0 {
+    fn change_counter(mut counter: ResMut<Counter>, events: Res<BackendEvents>) {
+        for event in &events.0 {
             if let event::Event::Key(key_event) = event {
+                if !is_key_press(key_event) {
+                    continue;
+                }
                 match key_event.code {
                     event::KeyCode::Left => counter.0 = counter.0.saturating_sub(1),
                     event::KeyCode::Right => counter.0 += 1,
@@ -80,14 +104,14 @@ mod counter {
         }
     }
 
-    fn render(counter: Res<Counter>, mut drawer: WidgetDrawer) {
-        let frame = drawer.get_frame();
-        let area = frame.area();
-        let area = Rect {
-            x: area.x + 10,
-            y: area.y + 10,
-            width: area.width / 2 - 20,
-            height: area.height - 20,
+    fn render(
+        counter: Res<Counter>,
+        focus: Res<Focus>,
+        layout: Res<LayoutRects>,
+        mut drawer: WidgetDrawer,
+    ) {
+        let Some(area) = layout.get("counter") else {
+            return;
         };
 
         let title = Line::from(" Counter ".bold());
@@ -97,10 +121,10 @@ mod counter {
             " Increment ".into(),
             "<Right>".blue().bold(),
         ]);
-        let block = Block::bordered()
-            .title(title.centered())
-            .title_bottom(instructions.centered())
-            .border_set(border::THICK);
+        let block = highlight_focused(
+            titled_block(title, instructions),
+            focus.is_focused("counter"),
+        );
 
         let counter_text = Text::from(vec![Line::from(vec![
             "Value: ".into(),
@@ -117,22 +141,26 @@ mod counter {
 
 mod progress {
     use ratatecs::prelude::*;
-    use ratatui::widgets::{Block, Gauge};
-    use symbols::border;
+    use ratatui::widgets::Gauge;
 
     #[derive(Resource)]
     struct Progress(u16);
 
     pub fn panel(app: &mut App) {
         app.insert_resource(Progress(0));
+        app.add_focusable("progress");
+        app.add_layout_in("progress_column", crate::app::centered(), ["progress"]);
 
-        app.add_systems(Update, change_progress);
+        app.add_systems(Update, change_progress.run_if(has_focus("progress")));
         app.add_systems(PostUpdate, render);
     }
 
-    fn change_progress(mut progress: ResMut<Progress>, event: Res<BackendEvent>) {
-        if let Some(event) = &event.0 {
+    fn change_progress(mut progress: ResMut<Progress>, events: Res<BackendEvents>) {
+        for event in &events.0 {
             if let event::Event::Key(key_event) = event {
+                if !is_key_press(key_event) {
+                    continue;
+                }
                 match key_event.code {
                     event::KeyCode::Down => progress.0 = progress.0.saturating_sub(1),
                     event::KeyCode::Up => progress.0 = (progress.0 + 1) % 101,
@@ -142,14 +170,14 @@ mod progress {
         }
     }
 
-    fn render(progress: Res<Progress>, mut drawer: WidgetDrawer) {
-        let frame = drawer.get_frame();
-        let area = frame.area();
-        let area = Rect {
-            x: area.width / 2 + area.x + 10,
-            y: area.y + 10,
-            width: area.width / 2 - 20,
-            height: area.height - 20,
+    fn render(
+        progress: Res<Progress>,
+        focus: Res<Focus>,
+        layout: Res<LayoutRects>,
+        mut drawer: WidgetDrawer,
+    ) {
+        let Some(area) = layout.get("progress") else {
+            return;
         };
 
         let title = Line::from(" Progress ".bold());
@@ -159,10 +187,10 @@ mod progress {
             " Increment ".into(),
             "<Up>".blue().bold(),
         ]);
-        let block = Block::bordered()
-            .title(title.centered())
-            .title_bottom(instructions.centered())
-            .border_set(border::THICK);
+        let block = highlight_focused(
+            titled_block(title, instructions),
+            focus.is_focused("progress"),
+        );
 
         drawer.push_widget(
             Box::new(
@@ -182,6 +210,8 @@ mod popup {
     use ratatui::widgets::{Block, Clear, Paragraph};
     use symbols::border;
 
+    use crate::app::AppAction;
+
     #[derive(States, Default, Debug, Hash, PartialEq, Eq, Clone)]
     enum PopupState {
         Open,
@@ -198,50 +228,41 @@ mod popup {
     fn toggle_popup(
         current_state: Res<State<PopupState>>,
         mut next_state: ResMut<NextState<PopupState>>,
-        event: Res<BackendEvent>,
+        mut actions: EventReader<AppAction>,
     ) {
-        if let Some(event) = &event.0 {
-            if let event::Event::Key(key_event) = event {
-                if key_event.code == event::KeyCode::Char(' ') {
-                    match current_state.get() {
-                        PopupState::Open => next_state.set(PopupState::Closed),
-                        PopupState::Closed => next_state.set(PopupState::Open),
-                    }
-                }
+        for _ in actions
+            .read()
+            .filter(|action| **action == AppAction::TogglePopup)
+        {
+            match current_state.get() {
+                PopupState::Open => next_state.set(PopupState::Closed),
+                PopupState::Closed => next_state.set(PopupState::Open),
             }
         }
     }
 
     fn render(mut drawer: WidgetDrawer) {
-        let frame = drawer.get_frame();
-        let area = frame.area();
-        let area = Rect {
-            x: area.width / 2 - 50,
-            y: area.height / 2 - 2,
-            width: 100,
-            height: 4,
-        };
+        let area = drawer.area();
+        let area = centered_rect(area, 100, 4);
 
         let title = Line::from(" About ".bold());
         let block = Block::bordered()
             .title(title.centered())
             .border_set(border::THICK);
 
-        drawer.push_widget(Box::new(Clear), area, 2);
+        let text = Paragraph::new(Text::from(vec![Line::from(vec![
+            "Hello from ".into(),
+            "ratatecs".red().bold(),
+            ", an experiment in building a TUI with ".into(),
+            "Ratatui".red().bold(),
+            " and ".into(),
+            "Bevy".red().bold(),
+            "!".into(),
+        ])]))
+        .centered()
+        .block(block);
         drawer.push_widget(
-            Box::new(
-                Paragraph::new(Text::from(vec![Line::from(vec![
-                    "Hello from ".into(),
-                    "ratatecs".red().bold(),
-                    ", an experiment in building a TUI with ".into(),
-                    "Ratatui".red().bold(),
-                    " and ".into(),
-                    "Bevy".red().bold(),
-                    "!".into(),
-                ])]))
-                .centered()
-                .block(block),
-            ),
+            Box::new(CompositeWidget::new().with(Clear).with(text)),
             area,
             2,
         );
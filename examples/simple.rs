@@ -1,13 +1,14 @@
 use ratatecs::prelude::*;
 
 fn main() {
-    App::new().add_plugins((RatatEcsPlugins, app::panel)).run();
+    App::new()
+        .add_plugins((RatatEcsPlugins::new(), app::panel))
+        .run();
 }
 
 mod app {
     use ratatecs::prelude::*;
-    use ratatui::widgets::{Block, Paragraph};
-    use symbols::border;
+    use ratatui::widgets::Paragraph;
 
     #[derive(Resource)]
     struct Counter(u32);
@@ -17,37 +18,31 @@ mod app {
         app.insert_resource(Counter(0));
 
         // Systems that update the state or react to user inputs
-        app.add_systems(Update, (exit_on_esc, change_counter));
+        app.add_systems(
+            Update,
+            (exit.run_if(on_key(event::KeyCode::Esc)), change_counter),
+        );
 
         // System to render thos panel
         app.add_systems(PostUpdate, render);
     }
 
-    fn exit_on_esc(event: Res<BackendEvent>, mut exit: EventWriter<AppExit>) {
-        if let Some(event) = &event.0 {
-            if let event::Event::Key(key_event) = event {
-                if key_event.code == event::KeyCode::Esc {
-                    exit.send(AppExit::Success);
-                }
-            }
-        }
+    fn exit(mut exit: EventWriter<AppExit>) {
+        exit.send(AppExit::Success);
     }
 
-    fn change_counter(mut counter: ResMut<Counter>, event: Res<BackendEvent>) {
-        if let Some(event) = &event.0 {
-            if let event::Event::Key(key_event) = event {
-                match key_event.code {
-                    event::KeyCode::Left => counter.0 = counter.0.saturating_sub(1),
-                    event::KeyCode::Right => counter.0 += 1,
-                    _ => (),
-                }
+    fn change_counter(mut counter: ResMut<Counter>, mut keys: EventReader<KeyPressed>) {
+        for KeyPressed(key_event) in keys.read() {
+            match key_event.code {
+                event::KeyCode::Left => counter.0 = counter.0.saturating_sub(1),
+                event::KeyCode::Right => counter.0 += 1,
+                _ => (),
             }
         }
     }
 
     fn render(counter: Res<Counter>, mut drawer: WidgetDrawer) {
-        let frame = drawer.get_frame();
-        let area = frame.area();
+        let area = drawer.area();
 
         let title = Line::from(" My Great TUI ".bold());
         let instructions = Line::from(vec![
@@ -58,10 +53,7 @@ mod app {
             " Quit ".into(),
             "<Esc> ".blue().bold(),
         ]);
-        let block = Block::bordered()
-            .title(title.centered())
-            .title_bottom(instructions.centered())
-            .border_set(border::THICK);
+        let block = titled_block(title, instructions);
 
         let counter_text = Text::from(vec![Line::from(vec![
             "Value: ".into(),
@@ -0,0 +1,66 @@
+//! Preferences of the user that panels adapt to.
+
+use std::env;
+
+use bevy_ecs::system::{Res, Resource};
+
+/// Whether the user asked for less motion, for animations like spinners and transitions to be
+/// disabled or replaced by a static frame.
+///
+/// Read from the `RATATECS_REDUCED_MOTION` environment variable when the plugin is built, see
+/// [`ReducedMotion::from_env`]. The test backends and the terminals given to
+/// [`TuiPlugin::with_terminal`](crate::TuiPlugin::with_terminal) always start without, so tests
+/// don't depend on the environment they run in. It can be changed at any time, like from a
+/// settings panel.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{snapshot, step};
+/// # use ratatui::widgets::Paragraph;
+/// #[derive(Resource, Default)]
+/// struct Spinner(usize);
+///
+/// fn spin(mut spinner: ResMut<Spinner>) {
+///     spinner.0 += 1;
+/// }
+///
+/// fn draw(spinner: Res<Spinner>, mut widgets: NonSendMut<WidgetsToDraw>) {
+///     let frame = ["|", "/", "-", "\\"][spinner.0 % 4];
+///     widgets.push_widget(Box::new(Paragraph::new(frame)), Rect::new(0, 0, 1, 1), 0);
+/// }
+///
+/// let mut app = App::new_tui_test(1, 1);
+/// app.init_resource::<Spinner>();
+/// app.add_systems(Update, (spin.run_if(reduced_motion_disabled()), draw).chain());
+/// step(&mut app, 1);
+/// assert_eq!(snapshot(&app), "/");
+///
+/// app.insert_resource(ReducedMotion(true));
+/// step(&mut app, 2);
+/// assert_eq!(snapshot(&app), "/");
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReducedMotion(pub bool);
+
+impl ReducedMotion {
+    /// Reduce motion when `RATATECS_REDUCED_MOTION` is set to anything but an empty string, `0`
+    /// or `false`.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// std::env::set_var("RATATECS_REDUCED_MOTION", "1");
+    /// assert_eq!(ReducedMotion::from_env(), ReducedMotion(true));
+    /// std::env::set_var("RATATECS_REDUCED_MOTION", "false");
+    /// assert_eq!(ReducedMotion::from_env(), ReducedMotion(false));
+    /// ```
+    pub fn from_env() -> Self {
+        let var = env::var("RATATECS_REDUCED_MOTION").unwrap_or_default();
+        Self(!matches!(var.to_lowercase().as_str(), "" | "0" | "false"))
+    }
+}
+
+/// Run condition true unless [`ReducedMotion`] is set, to only run animation systems when the
+/// user didn't ask for less motion.
+pub fn reduced_motion_disabled() -> impl FnMut(Res<ReducedMotion>) -> bool + Clone {
+    |reduced_motion: Res<ReducedMotion>| !reduced_motion.0
+}
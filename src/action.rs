@@ -0,0 +1,280 @@
+//! Map keys to application actions.
+//!
+//! Instead of matching on key codes in every panel, bind keys to variants of an action enum in
+//! an [`InputMap`], and read the actions as Bevy events.
+//!
+//! ```rust,no_run
+//! use ratatecs::prelude::*;
+//!
+//! #[derive(Event, Debug, Clone, PartialEq, Eq)]
+//! enum MyAction {
+//!     Quit,
+//!     TogglePopup,
+//! }
+//!
+//! fn exit_on_quit(mut actions: EventReader<MyAction>, mut exit: EventWriter<AppExit>) {
+//!     if actions.read().any(|action| *action == MyAction::Quit) {
+//!         exit.send(AppExit::Success);
+//!     }
+//! }
+//!
+//! App::new()
+//!     .add_plugins((
+//!         RatatEcsPlugins::new(),
+//!         InputMapPlugin::new(
+//!             InputMap::new()
+//!                 .bind(event::KeyCode::Esc, MyAction::Quit)
+//!                 .bind(KeyBinding::ctrl('c'), MyAction::Quit)
+//!                 .bind(' ', MyAction::TogglePopup),
+//!         ),
+//!     ))
+//!     .add_systems(Update, exit_on_quit)
+//!     .run();
+//! ```
+
+use bevy_app::{App, AppExit, Last, Plugin};
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{Res, Resource},
+};
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyModifiers};
+
+use crate::{input::is_key_press, BackendEvents, ReadBackendEvents};
+
+/// An action triggered by a key binding. Implemented for every cloneable Bevy event.
+pub trait Action: Event + Clone {}
+
+impl<T: Event + Clone> Action for T {}
+
+/// A key, with the modifiers that must be held for it to match.
+///
+/// Shift is ignored for characters, as the terminal already reports the shifted character:
+/// `KeyBinding::from('A')` matches Shift+A.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// The key with Ctrl held too, like Ctrl+C.
+    pub fn ctrl(key: impl Into<KeyBinding>) -> Self {
+        key.into().with_modifiers(KeyModifiers::CONTROL)
+    }
+
+    /// The key with Alt held too. Combine with [`KeyBinding::ctrl`] for Ctrl+Alt.
+    pub fn alt(key: impl Into<KeyBinding>) -> Self {
+        key.into().with_modifiers(KeyModifiers::ALT)
+    }
+
+    /// Require the modifiers to be held too.
+    pub fn with_modifiers(mut self, modifiers: KeyModifiers) -> Self {
+        self.modifiers |= modifiers;
+        self
+    }
+
+    /// Whether the key event matches this binding.
+    pub fn matches(&self, key: &crossterm::event::KeyEvent) -> bool {
+        let (mut expected, mut actual) = (self.modifiers, key.modifiers);
+        if let KeyCode::Char(_) = self.code {
+            expected.remove(KeyModifiers::SHIFT);
+            actual.remove(KeyModifiers::SHIFT);
+        }
+        self.code == key.code && expected == actual
+    }
+}
+
+impl From<KeyCode> for KeyBinding {
+    fn from(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+}
+
+impl From<char> for KeyBinding {
+    fn from(c: char) -> Self {
+        KeyCode::Char(c).into()
+    }
+}
+
+/// Key bindings to actions of type `A`.
+///
+/// A key can be bound to several actions, and an action to several keys. The map can be changed
+/// at runtime through `ResMut<InputMap<A>>` to remap keys.
+#[derive(Resource, Debug, Clone)]
+pub struct InputMap<A: Action> {
+    bindings: Vec<(KeyBinding, A)>,
+}
+
+impl<A: Action> Default for InputMap<A> {
+    fn default() -> Self {
+        Self {
+            bindings: Vec::new(),
+        }
+    }
+}
+
+impl<A: Action> InputMap<A> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind the key to the action.
+    pub fn bind(mut self, binding: impl Into<KeyBinding>, action: A) -> Self {
+        self.insert(binding, action);
+        self
+    }
+
+    /// Bind the key to the action.
+    pub fn insert(&mut self, binding: impl Into<KeyBinding>, action: A) {
+        self.bindings.push((binding.into(), action));
+    }
+
+    /// Remove all the actions bound to the key.
+    pub fn unbind(&mut self, binding: impl Into<KeyBinding>) {
+        let binding = binding.into();
+        self.bindings.retain(|(bound, _)| *bound != binding);
+    }
+
+    /// The key bindings, in the order they were added.
+    pub fn bindings(&self) -> impl Iterator<Item = &(KeyBinding, A)> {
+        self.bindings.iter()
+    }
+
+    /// The actions triggered by the key event.
+    pub fn actions_for<'a>(
+        &'a self,
+        key: &'a crossterm::event::KeyEvent,
+    ) -> impl Iterator<Item = &'a A> {
+        self.bindings
+            .iter()
+            .filter(move |(binding, _)| binding.matches(key))
+            .map(|(_, action)| action)
+    }
+}
+
+/// Insert the [`InputMap`] and send its actions as events when the keys are pressed.
+///
+/// Key releases don't trigger actions. The actions are sent at the end of the frame the keys
+/// were read in, and can be read with an `EventReader<A>` during the next frame.
+pub struct InputMapPlugin<A: Action> {
+    map: InputMap<A>,
+}
+
+impl<A: Action> InputMapPlugin<A> {
+    pub fn new(map: InputMap<A>) -> Self {
+        Self { map }
+    }
+}
+
+impl<A: Action> Plugin for InputMapPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<A>();
+        app.insert_resource(self.map.clone());
+        app.add_systems(Last, send_actions::<A>.after(ReadBackendEvents));
+    }
+}
+
+fn send_actions<A: Action>(
+    map: Res<InputMap<A>>,
+    events: Res<BackendEvents>,
+    mut actions: EventWriter<A>,
+) {
+    for event in &events.0 {
+        if let CrosstermEvent::Key(key_event) = event {
+            if is_key_press(key_event) {
+                actions.send_batch(map.actions_for(key_event).cloned());
+            }
+        }
+    }
+}
+
+/// The user asked to quit, sent by [`QuitPlugin`].
+///
+/// Unlike [`AppExit`], it doesn't exit the app: the app decides what to do, like asking for
+/// confirmation when there are unsaved changes. Add [`exit_on_quit_requested`] to always exit.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuitRequested;
+
+/// Send [`QuitRequested`] when one of the quit keys is pressed.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// #[derive(Resource)]
+/// struct Unsaved(bool);
+///
+/// #[derive(Resource, Default)]
+/// struct Confirming(bool);
+///
+/// fn quit(
+///     mut requests: EventReader<QuitRequested>,
+///     unsaved: Res<Unsaved>,
+///     mut confirming: ResMut<Confirming>,
+///     mut exit: EventWriter<AppExit>,
+/// ) {
+///     for _ in requests.read() {
+///         // Quitting again while asked for confirmation exits anyway
+///         if unsaved.0 && !confirming.0 {
+///             confirming.0 = true;
+///         } else {
+///             exit.send(AppExit::Success);
+///         }
+///     }
+/// }
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.add_plugins(QuitPlugin::new(event::KeyCode::Esc).bind(KeyBinding::ctrl('q')))
+///     .insert_resource(Unsaved(true))
+///     .init_resource::<Confirming>()
+///     .add_systems(Update, quit);
+///
+/// app.send_test_event(event::Event::Key(event::KeyCode::Esc.into()));
+/// step(&mut app, 2);
+/// assert!(app.world().resource::<Confirming>().0);
+/// assert_eq!(app.should_exit(), None);
+///
+/// let ctrl_q = event::KeyEvent::new(event::KeyCode::Char('q'), event::KeyModifiers::CONTROL);
+/// app.send_test_event(event::Event::Key(ctrl_q));
+/// step(&mut app, 2);
+/// assert_eq!(app.should_exit(), Some(AppExit::Success));
+/// ```
+pub struct QuitPlugin {
+    map: InputMap<QuitRequested>,
+}
+
+impl QuitPlugin {
+    /// Quit with `binding`.
+    pub fn new(binding: impl Into<KeyBinding>) -> Self {
+        Self {
+            map: InputMap::new().bind(binding, QuitRequested),
+        }
+    }
+
+    /// Quit with `binding` too.
+    pub fn bind(mut self, binding: impl Into<KeyBinding>) -> Self {
+        self.map.insert(binding, QuitRequested);
+        self
+    }
+}
+
+impl Plugin for QuitPlugin {
+    fn build(&self, app: &mut App) {
+        InputMapPlugin::new(self.map.clone()).build(app);
+    }
+}
+
+/// Exit with [`AppExit::Success`] on [`QuitRequested`], for apps
+/// that don't need to confirm.
+pub fn exit_on_quit_requested(
+    mut requests: EventReader<QuitRequested>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if requests.read().next().is_some() {
+        exit.send(AppExit::Success);
+    }
+}
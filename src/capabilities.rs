@@ -0,0 +1,108 @@
+//! The features supported by the terminal, for panels to adapt to the terminal they run in.
+
+use std::env;
+
+use bevy_ecs::system::Resource;
+
+use crate::color::ColorProfile;
+
+/// The features the terminal supports.
+///
+/// Detected from the environment when the plugin is built, see [`TerminalCapabilities::detect`].
+/// When [`TuiPlugin::keyboard_enhancement`](crate::TuiPlugin::keyboard_enhancement) is used,
+/// `kitty_keyboard` is the answer of the terminal to the crossterm query instead. The terminal is
+/// otherwise not queried, as terminals that don't answer delay the startup.
+///
+/// Like [`ColorProfile`], the test backends and the terminals given to
+/// [`TuiPlugin::with_terminal`](crate::TuiPlugin::with_terminal) support everything, and the
+/// resource can be overridden after adding the plugins.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{step, styled_snapshot};
+/// # use ratatui::{backend::TestBackend, widgets::Paragraph};
+/// fn status(capabilities: Res<TerminalCapabilities>, mut drawer: WidgetDrawer<TestBackend>) {
+///     let color = if capabilities.truecolor {
+///         Color::Rgb(255, 128, 0)
+///     } else {
+///         Color::Yellow
+///     };
+///     let hint = if capabilities.mouse { "click" } else { "enter" };
+///     let text = Paragraph::new(hint).fg(color);
+///     drawer.push_widget(Box::new(text), Rect::new(0, 0, 5, 1), 0);
+/// }
+///
+/// let mut app = App::new_tui_test(5, 1);
+/// app.add_systems(Update, status);
+/// step(&mut app, 1);
+/// let orange = Style::new().fg(Color::Rgb(255, 128, 0));
+/// assert_eq!(styled_snapshot(&app), [("click".to_string(), orange)]);
+///
+/// app.insert_resource(TerminalCapabilities {
+///     truecolor: false,
+///     mouse: false,
+///     ..TerminalCapabilities::ALL
+/// });
+/// step(&mut app, 1);
+/// assert_eq!(styled_snapshot(&app), [("enter".to_string(), Style::new().yellow())]);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TerminalCapabilities {
+    /// RGB colors, see [`ColorProfile::Truecolor`].
+    pub truecolor: bool,
+    /// Mouse events, once enabled with
+    /// [`TuiPlugin::enable_mouse`](crate::TuiPlugin::enable_mouse).
+    pub mouse: bool,
+    /// Pasted text sent as a single [`PasteEvent`](crate::input::PasteEvent).
+    pub bracketed_paste: bool,
+    /// Focus events, see [`TerminalFocus`](crate::input::TerminalFocus).
+    pub focus_change: bool,
+    /// The kitty keyboard protocol, with key releases and unambiguous modifiers.
+    pub kitty_keyboard: bool,
+}
+
+impl TerminalCapabilities {
+    /// Every feature supported.
+    pub const ALL: Self = Self {
+        truecolor: true,
+        mouse: true,
+        bracketed_paste: true,
+        focus_change: true,
+        kitty_keyboard: true,
+    };
+
+    /// Detect the capabilities of the terminal from the environment.
+    ///
+    /// Truecolor is detected like [`ColorProfile::detect`]. A `dumb` terminal supports nothing
+    /// else, while other terminals are expected to support the mouse, bracketed paste and focus
+    /// events, which terminals that don't ignore. The kitty keyboard protocol is assumed from the
+    /// terminals known to support it: kitty, foot, Alacritty, WezTerm and Ghostty.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// std::env::remove_var("NO_COLOR");
+    /// std::env::set_var("COLORTERM", "truecolor");
+    /// std::env::set_var("TERM", "xterm-kitty");
+    /// assert_eq!(TerminalCapabilities::detect(), TerminalCapabilities::ALL);
+    ///
+    /// std::env::remove_var("COLORTERM");
+    /// std::env::set_var("TERM", "dumb");
+    /// assert_eq!(TerminalCapabilities::detect(), TerminalCapabilities::default());
+    /// ```
+    pub fn detect() -> Self {
+        let var = |name| env::var(name).unwrap_or_default();
+        let term = var("TERM");
+        let dumb = term == "dumb";
+        let kitty_keyboard = term == "xterm-kitty"
+            || term.starts_with("foot")
+            || term == "alacritty"
+            || matches!(var("TERM_PROGRAM").as_str(), "WezTerm" | "ghostty");
+        Self {
+            truecolor: ColorProfile::detect() == ColorProfile::Truecolor,
+            mouse: !dumb,
+            bracketed_paste: !dumb,
+            focus_change: !dumb,
+            kitty_keyboard: !dumb && kitty_keyboard,
+        }
+    }
+}
@@ -0,0 +1,233 @@
+//! The colors supported by the terminal, to render styles the terminal can display.
+
+use std::env;
+
+use bevy_ecs::system::Resource;
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+
+/// The colors the terminal can display.
+///
+/// Detected from the environment when the plugin is built, see [`ColorProfile::detect`]. The test
+/// backends and the terminals given to [`TuiPlugin::with_terminal`](crate::TuiPlugin::with_terminal)
+/// always start with [`ColorProfile::Truecolor`], so tests don't depend on the terminal they run
+/// in. The profile can be overridden by inserting the resource after adding the plugins.
+///
+/// When rendering, the colors of the widgets are converted to the closest color of the profile,
+/// so widgets can use any color:
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// # use ratatui::{backend::TestBackend, widgets::Paragraph};
+/// fn draw(mut drawer: WidgetDrawer<TestBackend>) {
+///     let text = Paragraph::new("hi").fg(Color::Rgb(250, 10, 10)).bg(Color::Indexed(21));
+///     drawer.push_widget(Box::new(text), Rect::new(0, 0, 2, 1), 0);
+/// }
+///
+/// let mut app = App::new_tui_test(2, 1);
+/// app.insert_resource(ColorProfile::Ansi16);
+/// app.add_systems(Update, draw);
+/// step(&mut app, 1);
+///
+/// let terminal = app.world().non_send_resource::<TerminalWrapper<TestBackend>>();
+/// let cell = &terminal.terminal.backend().buffer()[(0, 0)];
+/// assert_eq!((cell.fg, cell.bg), (Color::LightRed, Color::Blue));
+///
+/// assert_eq!(ColorProfile::Ansi256.convert(Color::Rgb(0, 0, 255)), Color::Indexed(21));
+/// assert_eq!(ColorProfile::NoColor.convert(Color::Red), Color::Reset);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ColorProfile {
+    /// 24-bit RGB colors.
+    #[default]
+    Truecolor,
+    /// The 256 colors of the xterm palette.
+    Ansi256,
+    /// The 16 named colors.
+    Ansi16,
+    /// No colors at all, only the default foreground and background, and no modifiers except
+    /// [`Modifier::REVERSED`], which keeps selections visible without colors.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::step;
+    /// # use ratatui::{backend::TestBackend, widgets::Paragraph};
+    /// fn draw(mut drawer: WidgetDrawer<TestBackend>) {
+    ///     let text = Paragraph::new("hi").red().on_blue().bold().underlined();
+    ///     drawer.push_widget(Box::new(text), Rect::new(0, 0, 2, 1), 0);
+    ///     drawer.push_widget(Box::new(Paragraph::new("!").reversed()), Rect::new(2, 0, 1, 1), 0);
+    /// }
+    ///
+    /// std::env::set_var("NO_COLOR", "1");
+    /// assert_eq!(ColorProfile::detect(), ColorProfile::NoColor);
+    ///
+    /// let mut app = App::new_tui_test(3, 1);
+    /// app.insert_resource(ColorProfile::detect());
+    /// app.add_systems(Update, draw);
+    /// step(&mut app, 1);
+    ///
+    /// let terminal = app.world().non_send_resource::<TerminalWrapper<TestBackend>>();
+    /// let buffer = terminal.terminal.backend().buffer();
+    /// let style = |x| (buffer[(x, 0)].fg, buffer[(x, 0)].bg, buffer[(x, 0)].modifier);
+    /// assert_eq!(style(0), (Color::Reset, Color::Reset, Modifier::empty()));
+    /// assert_eq!(style(2), (Color::Reset, Color::Reset, Modifier::REVERSED));
+    /// ```
+    NoColor,
+}
+
+impl ColorProfile {
+    /// Detect the profile of the terminal from the environment.
+    ///
+    /// A non-empty `NO_COLOR` disables colors, as described on <https://no-color.org>. Otherwise
+    /// `COLORTERM` set to `truecolor` or `24bit` enables RGB colors, a `TERM` ending with
+    /// `256color` enables the 256 colors, a `dumb` terminal has no colors, and other terminals get
+    /// the 16 named colors.
+    pub fn detect() -> Self {
+        let var = |name| env::var(name).unwrap_or_default();
+        if !var("NO_COLOR").is_empty() {
+            return Self::NoColor;
+        }
+        if matches!(var("COLORTERM").as_str(), "truecolor" | "24bit") {
+            return Self::Truecolor;
+        }
+        match var("TERM").as_str() {
+            term if term.ends_with("256color") => Self::Ansi256,
+            "dumb" => Self::NoColor,
+            _ => Self::Ansi16,
+        }
+    }
+
+    /// The closest color to `color` supported by the profile.
+    pub fn convert(self, color: Color) -> Color {
+        match (self, color) {
+            (Self::Truecolor, _) | (_, Color::Reset) => color,
+            (Self::NoColor, _) => Color::Reset,
+            (Self::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(closest_indexed([r, g, b])),
+            (Self::Ansi256, _) => color,
+            (Self::Ansi16, Color::Rgb(..) | Color::Indexed(_)) => {
+                let rgb = rgb(color).unwrap_or_default();
+                let index = (0..16)
+                    .min_by_key(|index| distance(indexed(*index), rgb))
+                    .unwrap_or_default();
+                NAMED[usize::from(index)]
+            }
+            (Self::Ansi16, _) => color,
+        }
+    }
+
+    /// Convert the colors of every cell of `buffer` to the profile.
+    pub fn convert_buffer(self, buffer: &mut Buffer) {
+        if self == Self::Truecolor {
+            return;
+        }
+        let no_color = self == Self::NoColor;
+        for cell in &mut buffer.content {
+            cell.fg = self.convert(cell.fg);
+            cell.bg = self.convert(cell.bg);
+            cell.underline_color = self.convert(cell.underline_color);
+            if no_color {
+                cell.modifier &= Modifier::REVERSED;
+            }
+        }
+    }
+}
+
+/// The named colors, in the order of the first 16 colors of the palette.
+const NAMED: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The color in the xterm palette, `None` for the default color.
+pub(crate) fn rgb(color: Color) -> Option<[u8; 3]> {
+    let rgb = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => [r, g, b],
+        Color::Indexed(index) => indexed(index),
+        named => {
+            let index = NAMED.iter().position(|c| *c == named).unwrap_or_default();
+            indexed(index as u8)
+        }
+    };
+    Some(rgb)
+}
+
+pub(crate) fn indexed(index: u8) -> [u8; 3] {
+    const SYSTEM: [[u8; 3]; 16] = [
+        [0, 0, 0],
+        [205, 0, 0],
+        [0, 205, 0],
+        [205, 205, 0],
+        [0, 0, 238],
+        [205, 0, 205],
+        [0, 205, 205],
+        [229, 229, 229],
+        [127, 127, 127],
+        [255, 0, 0],
+        [0, 255, 0],
+        [255, 255, 0],
+        [92, 92, 255],
+        [255, 0, 255],
+        [0, 255, 255],
+        [255, 255, 255],
+    ];
+
+    match index {
+        0..=15 => SYSTEM[usize::from(index)],
+        16..=231 => {
+            let index = index - 16;
+            [index / 36, index / 6 % 6, index % 6].map(|level| CUBE_LEVELS[usize::from(level)])
+        }
+        232..=255 => {
+            let gray = 8 + (index - 232) * 10;
+            [gray; 3]
+        }
+    }
+}
+
+/// The closest color of the palette, skipping the first 16 whose values depend on the terminal's
+/// theme.
+fn closest_indexed(rgb: [u8; 3]) -> u8 {
+    let level = |value: u8| {
+        (0..6u8)
+            .min_by_key(|level| CUBE_LEVELS[usize::from(*level)].abs_diff(value))
+            .unwrap_or_default()
+    };
+    let [r, g, b] = rgb.map(level);
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    let average = rgb.iter().map(|value| u16::from(*value)).sum::<u16>() / 3;
+    let gray = 232 + ((average.saturating_sub(3) / 10).min(23)) as u8;
+
+    if distance(indexed(gray), rgb) < distance(indexed(cube), rgb) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| u32::from(a.abs_diff(b)).pow(2))
+        .sum()
+}
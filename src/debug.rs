@@ -0,0 +1,93 @@
+//! An overlay outlining the widgets, to debug layouts.
+
+use bevy_app::{App, Last, Plugin};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{Res, ResMut, Resource},
+};
+use crossterm::event::Event;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    widgets::{Block, Widget},
+};
+
+use crate::{action::KeyBinding, input::is_key_press, BackendEvents, ReadBackendEvents, Render};
+
+/// Draw the outline of every widget over the frame, labeled with its `z_order`.
+///
+/// The outlines are drawn when rendering, above all the widgets, so they include the widgets of
+/// every panel whatever the order of their systems. Disabled by default, toggle it with a key
+/// with the [`DebugOverlayPlugin`].
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{snapshot, step};
+/// # use ratatui::widgets::Paragraph;
+/// fn draw(mut widgets: NonSendMut<WidgetsToDraw>) {
+///     widgets.push_widget(Box::new(Paragraph::new("hidden")), Rect::new(0, 0, 8, 3), 5);
+///     widgets.push_widget(Box::new(Paragraph::new("right")), Rect::new(8, 1, 4, 1), 0);
+/// }
+///
+/// let mut app = App::new_tui_test(12, 3);
+/// app.insert_resource(DebugOverlay { enabled: true });
+/// app.add_systems(Update, draw);
+/// step(&mut app, 1);
+/// assert_eq!(snapshot(&app), "┌z5────┐\n│      │┌z0┐\n└──────┘");
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DebugOverlay {
+    pub enabled: bool,
+}
+
+impl DebugOverlay {
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+}
+
+/// Toggle the [`DebugOverlay`] when `binding` is pressed.
+pub struct DebugOverlayPlugin {
+    binding: KeyBinding,
+}
+
+impl DebugOverlayPlugin {
+    pub fn new(binding: impl Into<KeyBinding>) -> Self {
+        Self {
+            binding: binding.into(),
+        }
+    }
+}
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        let binding = self.binding;
+        app.init_resource::<DebugOverlay>();
+        app.add_systems(
+            Last,
+            (move |events: Res<BackendEvents>, mut overlay: ResMut<DebugOverlay>| {
+                for event in &events.0 {
+                    if let Event::Key(key_event) = event {
+                        if is_key_press(key_event) && binding.matches(key_event) {
+                            overlay.toggle();
+                        }
+                    }
+                }
+            })
+            // Shown right away when rendering in `Last`
+            .after(ReadBackendEvents)
+            .before(Render),
+        );
+    }
+}
+
+/// Outline the areas, with their `z_order`, in the order they were rendered.
+pub(crate) fn draw_outlines(outlines: &[(Rect, u32)], buf: &mut Buffer) {
+    for (area, z_order) in outlines {
+        Block::bordered()
+            .border_style(Style::new().magenta())
+            .title(format!("z{z_order}"))
+            .render(*area, buf);
+    }
+}
@@ -0,0 +1,1247 @@
+//! Widgets pushed by the panels each frame, and their rendering.
+
+use std::{
+    any::Any,
+    cell::{RefCell, RefMut},
+    collections::{HashMap, VecDeque},
+    io,
+    marker::PhantomData,
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use bevy_app::AppExit;
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    event::{Event, EventReader, EventWriter},
+    system::{NonSendMut, Res, ResMut, Resource, SystemParam},
+};
+use ratatui::{
+    backend::Backend,
+    buffer::Buffer,
+    layout::{Position, Rect, Size},
+    style::Color,
+    widgets::{StatefulWidgetRef, WidgetRef},
+    Frame,
+};
+
+use crate::{
+    color::ColorProfile,
+    debug::{self, DebugOverlay},
+    export,
+    layer::LayerId,
+    layout::offset_in,
+    viewport::{ViewportDrawer, Viewports},
+    DefaultBackend, TerminalSize, TerminalWrapper,
+};
+
+/// A widget to render this frame.
+///
+/// Widgets are rendered by increasing `z_order`. Widgets with the same `z_order` are rendered in
+/// the order they were pushed, so the last one pushed ends up on top.
+pub struct ScopedWidget {
+    pub widget: Box<dyn WidgetRef>,
+    pub area: Rect,
+    pub z_order: u32,
+    /// Push order of the widget during the frame, used to break ties between equal `z_order`.
+    pub sequence: u64,
+    /// Keep the background of the widgets below where this widget leaves the default background,
+    /// see [`WidgetsToDraw::push_blended`].
+    pub blend: bool,
+}
+
+/// The widgets to render this frame, and the widget states kept across frames.
+///
+/// Rendering drains the widgets, so panels must push their widgets again every frame to stay on
+/// screen. Each frame is drawn from an empty buffer: a panel that stops pushing, like a popup
+/// whose run condition became false, disappears entirely without leaving stale cells behind.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{assert_buffer_contains, buffer_to_string, step};
+/// # use ratatui::widgets::Paragraph;
+/// #[derive(Resource)]
+/// struct PopupOpen(bool);
+///
+/// fn popup(mut widgets: NonSendMut<WidgetsToDraw>) {
+///     widgets.push_widget(Box::new(Paragraph::new("popup")), Rect::new(2, 1, 5, 1), 1);
+/// }
+///
+/// let mut app = App::new_tui_test(10, 3);
+/// app.insert_resource(PopupOpen(true));
+/// app.add_systems(Update, popup.run_if(|open: Res<PopupOpen>| open.0));
+///
+/// step(&mut app, 1);
+/// assert_buffer_contains(&app, "popup");
+///
+/// app.insert_resource(PopupOpen(false));
+/// step(&mut app, 1);
+/// assert_eq!(buffer_to_string(&app).trim(), "");
+/// ```
+#[derive(Default)]
+pub struct WidgetsToDraw {
+    pub widgets: Vec<ScopedWidget>,
+    next_sequence: u64,
+    /// Widgets kept across frames, their `sequence` is their insertion order.
+    persistent: HashMap<&'static str, ScopedWidget>,
+    next_persistent_sequence: u64,
+    /// Areas of the widgets of the last rendered frame, bottom to top.
+    rendered: Vec<(Rect, RenderedWidget)>,
+    states: HashMap<u64, Rc<RefCell<dyn Any>>>,
+    next_state_id: u64,
+    /// Clear the terminal before the next draw, see [`WidgetDrawer::force_clear`].
+    pub(crate) force_clear: bool,
+    /// Files to save the next frame to, see [`WidgetDrawer::save_screenshot`].
+    screenshots: Vec<PathBuf>,
+}
+
+impl WidgetsToDraw {
+    /// Push a widget to render this frame.
+    ///
+    /// Widgets with the same `z_order` are rendered in the order they were pushed, so the last one
+    /// pushed ends up on top.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{snapshot, step};
+    /// # use ratatui::widgets::Paragraph;
+    /// fn draw(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     widgets.push_widget(Box::new(Paragraph::new("aaaaa")), Rect::new(0, 0, 5, 1), 0);
+    ///     widgets.push_widget(Box::new(Paragraph::new("bbb")), Rect::new(1, 0, 3, 1), 0);
+    ///     widgets.push_widget(Box::new(Paragraph::new("c")), Rect::new(2, 0, 1, 1), 0);
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(5, 1);
+    /// app.add_systems(Update, draw);
+    /// step(&mut app, 1);
+    /// assert_eq!(snapshot(&app), "abcba");
+    /// ```
+    pub fn push_widget(
+        &mut self,
+        widget: Box<dyn WidgetRef>,
+        area: Rect,
+        z_order: u32,
+    ) -> WidgetId {
+        self.push(widget, area, z_order, false)
+    }
+
+    /// Push a widget to render this frame over the background of the widgets below it.
+    ///
+    /// Cells the widget leaves with the default background, [`Color::Reset`], keep the background
+    /// of the widgets below, as if the widget was transparent. Overlays and tooltips pushed this
+    /// way show the panel behind them, unlike over a [`Clear`](ratatui::widgets::Clear).
+    ///
+    /// [`Color::Reset`]: ratatui::style::Color::Reset
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::step;
+    /// # use ratatui::{backend::TestBackend, widgets::{Block, Paragraph}};
+    /// fn draw(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     widgets.push_widget(Box::new(Block::new().on_blue()), Rect::new(0, 0, 6, 1), 0);
+    ///     // Both tooltips reset the background they're drawn on
+    ///     let tooltip = || Box::new(Paragraph::new("tip").style(Style::reset()));
+    ///     widgets.push_blended(tooltip(), Rect::new(0, 0, 3, 1), 1);
+    ///     widgets.push_widget(tooltip(), Rect::new(3, 0, 3, 1), 1);
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(6, 1);
+    /// app.add_systems(Update, draw);
+    /// step(&mut app, 1);
+    ///
+    /// let terminal = app.world().non_send_resource::<TerminalWrapper<TestBackend>>();
+    /// let buffer = terminal.terminal.backend().buffer();
+    /// assert_eq!(buffer[(0, 0)].symbol(), "t");
+    /// assert_eq!(buffer[(0, 0)].bg, Color::Blue);
+    /// assert_eq!(buffer[(3, 0)].bg, Color::Reset);
+    /// ```
+    pub fn push_blended(
+        &mut self,
+        widget: Box<dyn WidgetRef>,
+        area: Rect,
+        z_order: u32,
+    ) -> WidgetId {
+        self.push(widget, area, z_order, true)
+    }
+
+    fn push(
+        &mut self,
+        widget: Box<dyn WidgetRef>,
+        area: Rect,
+        z_order: u32,
+        blend: bool,
+    ) -> WidgetId {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.widgets.push(ScopedWidget {
+            widget,
+            area,
+            z_order,
+            sequence,
+            blend,
+        });
+        WidgetId(sequence)
+    }
+
+    /// Push a widget in `relative`, a rect relative to the top left corner of `parent`, like the
+    /// [`PanelArea`](crate::layout::PanelArea) of a child panel.
+    ///
+    /// The widget is clipped to `parent`, see [`layout::offset_in`](crate::layout::offset_in).
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{snapshot, step};
+    /// # use ratatui::widgets::Paragraph;
+    /// fn child(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     let parent = Rect::new(2, 1, 6, 2);
+    ///     let text = || Box::new(Paragraph::new("hello\nworld"));
+    ///     widgets.push_in(parent, Rect::new(0, 0, 5, 1), text(), 0);
+    ///     // Clipped to the bottom right corner of the parent
+    ///     widgets.push_in(parent, Rect::new(3, 1, 5, 2), text(), 0);
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(10, 4);
+    /// app.add_systems(Update, child);
+    /// step(&mut app, 1);
+    /// assert_eq!(snapshot(&app), "\n  hello\n     hel\n");
+    /// ```
+    pub fn push_in(
+        &mut self,
+        parent: Rect,
+        relative: Rect,
+        widget: Box<dyn WidgetRef>,
+        z_order: u32,
+    ) -> WidgetId {
+        self.push_widget(widget, offset_in(parent, relative), z_order)
+    }
+
+    /// Push a closure drawing directly to the buffer this frame, in place of a widget.
+    ///
+    /// The closure is called once, with the area clipped to the terminal, and ordered like the
+    /// other widgets.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{assert_buffer_contains, step};
+    /// fn checkerboard(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     widgets.push_fn(Rect::new(0, 0, 4, 1), 0, |area, buf| {
+    ///         for x in area.left()..area.right() {
+    ///             buf[(x, area.y)].set_char(if x % 2 == 0 { '#' } else { '.' });
+    ///         }
+    ///     });
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(4, 1);
+    /// app.add_systems(Update, checkerboard);
+    /// step(&mut app, 1);
+    /// assert_buffer_contains(&app, "#.#.");
+    /// ```
+    pub fn push_fn(
+        &mut self,
+        area: Rect,
+        z_order: u32,
+        draw: impl FnOnce(Rect, &mut Buffer) + 'static,
+    ) -> WidgetId {
+        let widget = FnWidget(RefCell::new(Some(draw)));
+        self.push_widget(Box::new(widget), area, z_order)
+    }
+
+    /// Push a closure drawing to a buffer scoped to `area` this frame, like a braille canvas
+    /// computed cell by cell.
+    ///
+    /// Unlike with [`WidgetsToDraw::push_fn`], the closure can't write outside `area`: it's given
+    /// a buffer covering only `area`, holding what the widgets below drew there, that is copied
+    /// back after it ran. Drawing methods like [`Buffer::set_string`] clip to it, and
+    /// [`Buffer::cell_mut`] returns `None` outside of it, while indexing outside of it panics.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{snapshot, step};
+    /// fn canvas(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     widgets.with_buffer(Rect::new(1, 0, 2, 1), 0, |buf, area| {
+    ///         buf[(area.x, area.y)].set_char('⣿');
+    ///         // Clipped to the area
+    ///         buf.set_string(area.x + 1, area.y, "xyz", Style::new());
+    ///         assert!(buf.cell_mut((area.x, area.y + 1)).is_none());
+    ///     });
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(5, 2);
+    /// app.add_systems(Update, canvas);
+    /// step(&mut app, 1);
+    /// assert_eq!(snapshot(&app), " ⣿x\n");
+    /// ```
+    ///
+    /// The closure isn't called when `area` is entirely off screen:
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{buffer, step};
+    /// fn off_screen(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     widgets.with_buffer(Rect::new(20, 0, 3, 1), 0, |_, _| unreachable!());
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(10, 2);
+    /// app.add_systems(Update, off_screen);
+    /// step(&mut app, 2);
+    /// assert_eq!(buffer(&app).area, Rect::new(0, 0, 10, 2));
+    /// ```
+    pub fn with_buffer(
+        &mut self,
+        area: Rect,
+        z_order: u32,
+        draw: impl FnMut(&mut Buffer, Rect) + 'static,
+    ) -> WidgetId {
+        let widget = ScopedBufferWidget(RefCell::new(draw));
+        self.push_widget(Box::new(widget), area, z_order)
+    }
+
+    /// Push a widget rendered on content of `content_size`, possibly larger than `area`, showing
+    /// the part of it starting at `offset` in `area`.
+    ///
+    /// The widget doesn't have to handle scrolling itself: like a paragraph of all the lines of
+    /// a log, it's rendered whole and only the visible window is copied. Past the end of the
+    /// content, the area is left as is. Use `area.width` as the content width to only scroll
+    /// vertically.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{snapshot, step};
+    /// # use ratatui::{layout::Size, widgets::Paragraph};
+    /// fn logs(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     let lines = Paragraph::new("line 1\nline 2\nline 3\nline 4");
+    ///     let content_size = Size::new(6, 4);
+    ///     let offset = Position::new(2, 1);
+    ///     widgets.push_scrollable(Box::new(lines), content_size, offset, Rect::new(0, 0, 6, 2), 0);
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(6, 2);
+    /// app.add_systems(Update, logs);
+    /// step(&mut app, 1);
+    /// assert_eq!(snapshot(&app), "ne 2\nne 3");
+    /// ```
+    pub fn push_scrollable(
+        &mut self,
+        widget: Box<dyn WidgetRef>,
+        content_size: Size,
+        offset: Position,
+        area: Rect,
+        z_order: u32,
+    ) -> WidgetId {
+        let widget = ScrollableWidget {
+            widget,
+            content_size,
+            offset,
+        };
+        self.push_widget(Box::new(widget), area, z_order)
+    }
+
+    /// Insert a widget rendered every frame until it's removed with
+    /// [`WidgetsToDraw::remove_persistent`].
+    ///
+    /// Inserting again with the same `id` replaces the widget, its area and its `z_order`, but
+    /// keeps its place among the other persistent widgets. Persistent widgets are rendered by
+    /// increasing `z_order` like the other widgets. With the same `z_order`, persistent widgets
+    /// are rendered below the widgets pushed during the frame, in the order their ids were first
+    /// inserted.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{assert_buffer_contains, step};
+    /// # use ratatui::widgets::Paragraph;
+    /// fn frame(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     let title = Paragraph::new("title");
+    ///     widgets.insert_persistent("title", Box::new(title), Rect::new(0, 0, 10, 1), 0);
+    /// }
+    ///
+    /// fn status(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     widgets.push_widget(Box::new(Paragraph::new("ok")), Rect::new(0, 0, 2, 1), 0);
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(10, 1);
+    /// app.add_systems(Startup, frame);
+    /// app.add_systems(Update, status);
+    /// step(&mut app, 2);
+    /// assert_buffer_contains(&app, "oktle");
+    /// ```
+    ///
+    /// A panel that only changes with a resource can insert its widget from a system running
+    /// with Bevy's [`resource_changed`](bevy_ecs::schedule::common_conditions::resource_changed)
+    /// condition, so the panel costs nothing on the frames where the resource didn't change. Its
+    /// widget is still rendered every frame, and ratatui only writes the cells that changed to the
+    /// terminal.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{assert_buffer_contains, step};
+    /// # use ratatui::widgets::Paragraph;
+    /// #[derive(Resource, Default)]
+    /// struct Counter(u32);
+    ///
+    /// #[derive(Resource, Default)]
+    /// struct Updates(u32);
+    ///
+    /// fn counter(
+    ///     counter: Res<Counter>,
+    ///     mut widgets: NonSendMut<WidgetsToDraw>,
+    ///     mut updates: ResMut<Updates>,
+    /// ) {
+    ///     let text = Paragraph::new(format!("count: {}", counter.0));
+    ///     widgets.insert_persistent("counter", Box::new(text), Rect::new(0, 0, 10, 1), 0);
+    ///     updates.0 += 1;
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(10, 1);
+    /// app.init_resource::<Counter>().init_resource::<Updates>();
+    /// app.add_systems(Update, counter.run_if(resource_changed::<Counter>));
+    /// step(&mut app, 3);
+    /// assert_buffer_contains(&app, "count: 0");
+    ///
+    /// app.world_mut().resource_mut::<Counter>().0 = 1;
+    /// step(&mut app, 3);
+    /// assert_buffer_contains(&app, "count: 1");
+    /// assert_eq!(app.world().resource::<Updates>().0, 2);
+    /// ```
+    pub fn insert_persistent(
+        &mut self,
+        id: &'static str,
+        widget: Box<dyn WidgetRef>,
+        area: Rect,
+        z_order: u32,
+    ) {
+        let sequence = match self.persistent.get(id) {
+            Some(previous) => previous.sequence,
+            None => {
+                self.next_persistent_sequence += 1;
+                self.next_persistent_sequence - 1
+            }
+        };
+        self.persistent.insert(
+            id,
+            ScopedWidget {
+                widget,
+                area,
+                z_order,
+                sequence,
+                blend: false,
+            },
+        );
+    }
+
+    /// Drop the widgets pushed so far this frame. Persistent widgets are kept.
+    pub fn clear(&mut self) {
+        self.widgets.clear();
+        self.next_sequence = 0;
+    }
+
+    /// The number of widgets pushed so far this frame, without the persistent widgets.
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    /// The area and `z_order` of the widgets pushed so far this frame, in push order.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatui::widgets::Clear;
+    /// let mut widgets = WidgetsToDraw::default();
+    /// widgets.push_widget(Box::new(Clear), Rect::new(0, 0, 4, 2), 1);
+    /// widgets.push_widget(Box::new(Clear), Rect::new(1, 1, 2, 1), 0);
+    /// assert_eq!(widgets.len(), 2);
+    /// assert_eq!(
+    ///     widgets.iter().collect::<Vec<_>>(),
+    ///     [(Rect::new(0, 0, 4, 2), 1), (Rect::new(1, 1, 2, 1), 0)],
+    /// );
+    ///
+    /// widgets.clear();
+    /// assert!(widgets.is_empty());
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (Rect, u32)> + '_ {
+        self.widgets
+            .iter()
+            .map(|scoped| (scoped.area, scoped.z_order))
+    }
+
+    /// Stop rendering the persistent widget inserted with this `id`.
+    pub fn remove_persistent(&mut self, id: &str) {
+        self.persistent.remove(id);
+    }
+
+    /// Store the state of a stateful widget so that it's kept across frames.
+    pub fn add_widget_state<S: 'static>(&mut self, state: S) -> WidgetStateId<S> {
+        let id = self.next_state_id;
+        self.next_state_id += 1;
+        self.states.insert(id, Rc::new(RefCell::new(state)));
+        WidgetStateId {
+            id,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn widget_state_mut<S: 'static>(&self, id: WidgetStateId<S>) -> Option<RefMut<'_, S>> {
+        let state = self.states.get(&id.id)?.borrow_mut();
+        RefMut::filter_map(state, |state| state.downcast_mut::<S>()).ok()
+    }
+
+    pub fn remove_widget_state<S: 'static>(&mut self, id: WidgetStateId<S>) {
+        self.states.remove(&id.id);
+    }
+
+    /// The topmost widget of the last rendered frame covering the cell at `column` and `row`,
+    /// like the position of a [`MouseEvent`](crate::input::MouseEvent).
+    ///
+    /// The [`WidgetId`] is the one returned when the widget was pushed during that frame. Widgets
+    /// are only hit inside the part of their area that was rendered on the terminal. When the
+    /// topmost widget is persistent, this is `None` and [`WidgetsToDraw::hit_test_persistent`]
+    /// has its id.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatui::widgets::{Block, Clear};
+    /// #[derive(Resource, Default)]
+    /// struct Pushed(Vec<WidgetId>);
+    ///
+    /// fn push(mut widgets: NonSendMut<WidgetsToDraw>, mut pushed: ResMut<Pushed>) {
+    ///     pushed.0 = vec![
+    ///         widgets.push_widget(Box::new(Block::bordered()), Rect::new(0, 0, 20, 10), 0),
+    ///         widgets.push_widget(Box::new(Clear), Rect::new(5, 2, 10, 4), 1),
+    ///         // Below the previous widget despite being pushed after it
+    ///         widgets.push_widget(Box::new(Clear), Rect::new(10, 4, 10, 4), 0),
+    ///     ];
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(20, 10);
+    /// app.init_resource::<Pushed>();
+    /// app.add_systems(Update, push);
+    /// ratatecs::testing::step(&mut app, 1);
+    ///
+    /// let pushed = &app.world().resource::<Pushed>().0;
+    /// let widgets = app.world().non_send_resource::<WidgetsToDraw>();
+    /// assert_eq!(widgets.hit_test(1, 1), Some(pushed[0]));
+    /// assert_eq!(widgets.hit_test(6, 3), Some(pushed[1]));
+    /// assert_eq!(widgets.hit_test(12, 5), Some(pushed[1]));
+    /// assert_eq!(widgets.hit_test(16, 7), Some(pushed[2]));
+    /// assert_eq!(widgets.hit_test(25, 5), None);
+    /// ```
+    ///
+    /// A [`Clear`](ratatui::widgets::Clear) takes up its area like any other widget, so a popup
+    /// made of a `Clear` and its content at the same area and `z_order` gets the clicks over the
+    /// panel beneath it. The content, pushed last, is the one hit:
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatui::widgets::{Block, Clear, Paragraph};
+    /// #[derive(Resource, Default)]
+    /// struct Pushed(Vec<WidgetId>);
+    ///
+    /// fn push(mut widgets: NonSendMut<WidgetsToDraw>, mut pushed: ResMut<Pushed>) {
+    ///     let popup = Rect::new(5, 2, 10, 4);
+    ///     pushed.0 = vec![
+    ///         widgets.push_widget(Box::new(Block::bordered()), Rect::new(0, 0, 20, 10), 1),
+    ///         widgets.push_widget(Box::new(Clear), popup, 2),
+    ///         widgets.push_widget(Box::new(Paragraph::new("popup")), popup, 2),
+    ///     ];
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(20, 10);
+    /// app.init_resource::<Pushed>();
+    /// app.add_systems(Update, push);
+    /// ratatecs::testing::step(&mut app, 1);
+    ///
+    /// let pushed = &app.world().resource::<Pushed>().0;
+    /// let widgets = app.world().non_send_resource::<WidgetsToDraw>();
+    /// assert_eq!(widgets.hit_test(1, 1), Some(pushed[0]));
+    /// assert_eq!(widgets.hit_test(5, 2), Some(pushed[2]));
+    /// // Blank cells of the paragraph still belong to it
+    /// assert_eq!(widgets.hit_test(14, 5), Some(pushed[2]));
+    /// assert_eq!(widgets.hit_test(15, 5), Some(pushed[0]));
+    /// ```
+    pub fn hit_test(&self, column: u16, row: u16) -> Option<WidgetId> {
+        match self.topmost_rendered(column, row)? {
+            RenderedWidget::Transient(sequence) => Some(WidgetId(*sequence)),
+            RenderedWidget::Persistent(_) => None,
+        }
+    }
+
+    /// The id of the topmost widget of the last rendered frame covering the cell at `column` and
+    /// `row`, if it's a persistent widget.
+    pub fn hit_test_persistent(&self, column: u16, row: u16) -> Option<&'static str> {
+        match self.topmost_rendered(column, row)? {
+            RenderedWidget::Transient(_) => None,
+            RenderedWidget::Persistent(id) => Some(id),
+        }
+    }
+
+    fn topmost_rendered(&self, column: u16, row: u16) -> Option<&RenderedWidget> {
+        let position = Position::new(column, row);
+        self.rendered
+            .iter()
+            .rev()
+            .find(|(area, _)| area.contains(position))
+            .map(|(_, widget)| widget)
+    }
+}
+
+enum RenderedWidget {
+    /// A widget pushed for the frame, with its push order.
+    Transient(u64),
+    Persistent(&'static str),
+}
+
+/// A widget pushed during the frame, as returned by [`WidgetsToDraw::push_widget`] and the other
+/// push methods.
+///
+/// Ids follow the push order and start over each frame, so a panel pushing the same widgets
+/// every frame gets the same ids, and can compare them with the result of
+/// [`WidgetsToDraw::hit_test`] for the last rendered frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WidgetId(u64);
+
+/// Handle to the state of a stateful widget, as returned by
+/// [`WidgetDrawer::add_widget_state`].
+///
+/// It can be stored in a resource or a component to push the same widget state every frame.
+pub struct WidgetStateId<S> {
+    id: u64,
+    marker: PhantomData<fn() -> S>,
+}
+
+impl<S> Clone for WidgetStateId<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for WidgetStateId<S> {}
+
+impl<S> PartialEq for WidgetStateId<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<S> Eq for WidgetStateId<S> {}
+
+impl<S> std::hash::Hash for WidgetStateId<S> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl<S> std::fmt::Debug for WidgetStateId<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WidgetStateId").field(&self.id).finish()
+    }
+}
+
+struct StatefulWidget<S> {
+    widget: Box<dyn StatefulWidgetRef<State = S>>,
+    state: Rc<RefCell<dyn Any>>,
+}
+
+impl<S: 'static> WidgetRef for StatefulWidget<S> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(state) = self.state.borrow_mut().downcast_mut::<S>() {
+            self.widget.render_ref(area, buf, state);
+        }
+    }
+}
+
+/// Wraps a closure pushed with [`WidgetsToDraw::push_fn`], as widgets are rendered by reference.
+struct FnWidget<F>(RefCell<Option<F>>);
+
+impl<F: FnOnce(Rect, &mut Buffer)> WidgetRef for FnWidget<F> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(draw) = self.0.borrow_mut().take() {
+            draw(area, buf);
+        }
+    }
+}
+
+/// Wraps a closure pushed with [`WidgetsToDraw::with_buffer`].
+struct ScopedBufferWidget<F>(RefCell<F>);
+
+impl<F: FnMut(&mut Buffer, Rect)> WidgetRef for ScopedBufferWidget<F> {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        // Off screen, the area is empty and may be outside of the frame
+        let area = area.intersection(buf.area);
+        if area.is_empty() {
+            return;
+        }
+        let mut scoped = Buffer::empty(area);
+        for position in area.positions() {
+            scoped[position] = buf[position].clone();
+        }
+        (self.0.borrow_mut())(&mut scoped, area);
+        // Unlike `Buffer::merge`, never resizes the frame
+        for position in area.positions() {
+            buf[position] = scoped[position].clone();
+        }
+    }
+}
+
+/// Wraps a widget pushed with [`WidgetsToDraw::push_scrollable`].
+struct ScrollableWidget {
+    widget: Box<dyn WidgetRef>,
+    content_size: Size,
+    offset: Position,
+}
+
+impl WidgetRef for ScrollableWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        let content_area = Rect::new(0, 0, self.content_size.width, self.content_size.height);
+        let mut content = Buffer::empty(content_area);
+        self.widget.render_ref(content_area, &mut content);
+
+        for row in 0..area.height {
+            for column in 0..area.width {
+                let source = Position::new(
+                    self.offset.x.saturating_add(column),
+                    self.offset.y.saturating_add(row),
+                );
+                // The content is smaller than the area when scrolled to its end
+                if !content_area.contains(source) {
+                    continue;
+                }
+                buf[(area.x + column, area.y + row)] = content[source].clone();
+            }
+        }
+    }
+}
+
+/// Where to show the terminal cursor after rendering. The cursor is hidden when `None`.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CursorState {
+    pub position: Option<(u16, u16)>,
+}
+
+/// The area of the terminal rendered to, as of the last render or resize.
+///
+/// It's the whole terminal, unless rendering to a smaller viewport with
+/// [`TuiPlugin::viewport`](crate::TuiPlugin::viewport).
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameArea(pub Rect);
+
+/// An error writing a frame to the terminal, like a broken pipe or a closed terminal, sent
+/// instead of silently rendering nothing.
+///
+/// Other failures to update the terminal while it's set up, like suspending the app on Ctrl+Z or
+/// setting its [`TerminalTitle`](crate::title::TerminalTitle), are sent the same way, as printing them
+/// would write over the frame.
+///
+/// The app keeps running after an error, add [`exit_on_render_error`] to exit instead.
+///
+/// ```rust
+/// # use std::io::{self, Write};
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// struct Closed;
+///
+/// impl Write for Closed {
+///     fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+///         Err(io::ErrorKind::BrokenPipe.into())
+///     }
+///
+///     fn flush(&mut self) -> io::Result<()> {
+///         Err(io::ErrorKind::BrokenPipe.into())
+///     }
+/// }
+///
+/// fn check(mut errors: EventReader<RenderError>) {
+///     let error = errors.read().next().expect("the frame was rendered");
+///     assert_eq!(error.0.kind(), io::ErrorKind::BrokenPipe);
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugins(RatatEcsPlugins::new().set(TuiPlugin::with_writer(Box::new(Closed))));
+/// app.add_systems(Last, (check, exit_on_render_error));
+/// step(&mut app, 1);
+/// assert_eq!(app.should_exit(), Some(AppExit::error()));
+/// ```
+#[derive(Event, Debug)]
+pub struct RenderError(pub io::Error);
+
+/// A screenshot requested with [`WidgetDrawer::save_screenshot`] that couldn't be saved.
+#[derive(Event, Debug)]
+pub struct ScreenshotError {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+/// Exit with [`AppExit::error`] when a frame can't be rendered, see [`RenderError`].
+pub fn exit_on_render_error(mut errors: EventReader<RenderError>, mut exit: EventWriter<AppExit>) {
+    if errors.read().next().is_some() {
+        exit.send(AppExit::error());
+    }
+}
+
+/// A copy of the buffer of the last rendered frame, with every backend.
+///
+/// Unlike the test backends, the real backends don't keep what they drew. Copying the buffer
+/// takes time each frame, so it's only done when this resource is inserted, like with
+/// `app.init_resource::<LastFrame>()`.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::{export::to_text, testing::step};
+/// # use ratatui::widgets::Paragraph;
+/// fn hello(mut widgets: NonSendMut<WidgetsToDraw>) {
+///     widgets.push_widget(Box::new(Paragraph::new("hello")), Rect::new(0, 0, 5, 1), 0);
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugins(RatatEcsPlugins::new().set(TuiPlugin::with_writer(Box::new(std::io::sink()))))
+///     .init_resource::<LastFrame>()
+///     .add_systems(Update, hello);
+/// step(&mut app, 1);
+/// let text = to_text(&app.world().resource::<LastFrame>().0);
+/// assert!(text.starts_with("hello"));
+/// ```
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct LastFrame(pub Buffer);
+
+/// Timing of the rendered frames.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FrameStats {
+    /// How long rendering the last frame took.
+    pub last_frame: Duration,
+    /// Frames rendered per second, averaged over the last [`FrameStats::WINDOW`] frames.
+    pub fps: f32,
+    /// Number of frames rendered since the start.
+    pub frame_count: u64,
+    last_render: Option<Instant>,
+    intervals: VecDeque<Duration>,
+}
+
+impl FrameStats {
+    /// Number of frames the frame rate is averaged over.
+    pub const WINDOW: usize = 60;
+
+    fn record(&mut self, started: Instant, duration: Duration) {
+        self.last_frame = duration;
+        self.frame_count += 1;
+        if let Some(last_render) = self.last_render {
+            if self.intervals.len() == Self::WINDOW {
+                self.intervals.pop_front();
+            }
+            self.intervals.push_back(started - last_render);
+            let total: Duration = self.intervals.iter().sum();
+            if !total.is_zero() {
+                self.fps = self.intervals.len() as f32 / total.as_secs_f32();
+            }
+        }
+        self.last_render = Some(started);
+    }
+}
+
+/// Whether the next frame must be rendered, when rendering only when needed with
+/// [`TuiPlugin::render_when_dirty`](crate::TuiPlugin::render_when_dirty).
+///
+/// It's set when events are received from the terminal, or with
+/// [`WidgetDrawer::request_redraw`], and cleared after rendering. While it's not set, the
+/// widgets pushed during the frame are dropped and the terminal keeps showing the last rendered
+/// frame.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NeedsRedraw(pub bool);
+
+impl Default for NeedsRedraw {
+    fn default() -> Self {
+        // The first frame is always rendered
+        Self(true)
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render<B: Backend + 'static>(
+    mut widget_drawer: WidgetDrawer<B>,
+    mut size: ResMut<TerminalSize>,
+    mut stats: ResMut<FrameStats>,
+    profile: Option<Res<ColorProfile>>,
+    mut errors: EventWriter<RenderError>,
+    mut screenshot_errors: EventWriter<ScreenshotError>,
+    debug_overlay: Option<Res<DebugOverlay>>,
+    last_frame: Option<ResMut<LastFrame>>,
+) {
+    if let Some(redraw) = widget_drawer.redraw.as_deref_mut() {
+        if !redraw.0 {
+            widget_drawer.widgets.clear();
+            return;
+        }
+        redraw.0 = false;
+    }
+
+    let started = Instant::now();
+    if std::mem::take(&mut widget_drawer.widgets.force_clear) {
+        if let Err(err) = widget_drawer.terminal.terminal.clear() {
+            errors.send(RenderError(err));
+        }
+    }
+    let completed = widget_drawer.terminal.terminal.draw(|frame| {
+        // The frame starts from an empty buffer, ratatui resets it after each draw
+        let buf = frame.buffer_mut();
+        let buffer_area = buf.area;
+        let WidgetsToDraw {
+            widgets,
+            persistent,
+            rendered,
+            ..
+        } = &mut *widget_drawer.widgets;
+        rendered.clear();
+        let debug_overlay = debug_overlay.is_some_and(|overlay| overlay.enabled);
+        let mut outlines = Vec::new();
+        let mut render_one = |scoped: &ScopedWidget, rendered_widget| {
+            // Widgets larger than the terminal are clipped rather than writing out of bounds
+            let area = scoped.area.intersection(buffer_area);
+            if scoped.blend {
+                render_blended(&*scoped.widget, area, buf);
+            } else {
+                scoped.widget.render_ref(area, buf);
+            }
+            rendered.push((area, rendered_widget));
+            if debug_overlay {
+                outlines.push((area, scoped.z_order));
+            }
+        };
+
+        widgets.sort_by_key(|sw| (sw.z_order, sw.sequence));
+        let mut persistent: Vec<_> = persistent.iter().collect();
+        persistent.sort_by_key(|(_, sw)| (sw.z_order, sw.sequence));
+        let mut persistent = persistent.into_iter().peekable();
+        for scoped in widgets.drain(..) {
+            while let Some((id, below)) =
+                persistent.next_if(|(_, below)| below.z_order <= scoped.z_order)
+            {
+                render_one(below, RenderedWidget::Persistent(id));
+            }
+            render_one(&scoped, RenderedWidget::Transient(scoped.sequence));
+        }
+        for (id, above) in persistent {
+            render_one(above, RenderedWidget::Persistent(id));
+        }
+        debug::draw_outlines(&outlines, buf);
+        if let Some(profile) = &profile {
+            profile.convert_buffer(buf);
+        }
+
+        if let Some(position) = widget_drawer.cursor.position {
+            frame.set_cursor_position(position);
+        }
+    });
+    // Start the next frame's push order over, the widgets were drained while drawing
+    widget_drawer.widgets.clear();
+
+    // The terminal resizes itself before drawing if the backend size changed
+    match completed {
+        Ok(completed) => {
+            if let Some(mut last_frame) = last_frame {
+                last_frame.0.clone_from(completed.buffer);
+            }
+            for path in widget_drawer.widgets.screenshots.drain(..) {
+                if let Err(error) = export::save(completed.buffer, &path) {
+                    screenshot_errors.send(ScreenshotError { path, error });
+                }
+            }
+            widget_drawer
+                .frame_area
+                .set_if_neq(FrameArea(completed.area));
+        }
+        // Not printed, as it would garble the screen while the terminal is set up, and print on
+        // every frame once the terminal is gone
+        Err(err) => {
+            errors.send(RenderError(err));
+        }
+    }
+    // The size of the terminal, rather than of the viewport in the completed frame
+    if let Ok(terminal_size) = widget_drawer.terminal.terminal.size() {
+        size.set_if_neq(TerminalSize(terminal_size.width, terminal_size.height));
+    }
+    stats.record(started, started.elapsed());
+}
+
+/// Render `widget`, then put back the background below it where it left the default one.
+fn render_blended(widget: &dyn WidgetRef, area: Rect, buf: &mut Buffer) {
+    let below: Vec<_> = area.positions().map(|position| buf[position].bg).collect();
+    widget.render_ref(area, buf);
+    for (position, bg) in area.positions().zip(below) {
+        let cell = &mut buf[position];
+        if cell.bg == Color::Reset {
+            cell.bg = bg;
+        }
+    }
+}
+
+/// Access to the widgets to draw and the terminal from a system.
+///
+/// The backend defaults to [`DefaultBackend`], as used by [`TuiPlugin::new`], so most apps can
+/// use `WidgetDrawer` without a type parameter. Systems shared with the other backends, like for
+/// tests, can be generic over `B`.
+///
+/// [`TuiPlugin::new`]: crate::TuiPlugin::new
+#[derive(SystemParam)]
+pub struct WidgetDrawer<'w, B: Backend + 'static = DefaultBackend> {
+    widgets: NonSendMut<'w, WidgetsToDraw>,
+    terminal: NonSendMut<'w, TerminalWrapper<B>>,
+    cursor: ResMut<'w, CursorState>,
+    viewports: Res<'w, Viewports>,
+    frame_area: ResMut<'w, FrameArea>,
+    redraw: Option<ResMut<'w, NeedsRedraw>>,
+}
+
+impl<B: Backend> WidgetDrawer<'_, B> {
+    /// Push a widget to render this frame.
+    ///
+    /// Widgets with the same `z_order` are rendered in the order they were pushed, so the last one
+    /// pushed ends up on top.
+    pub fn push_widget(
+        &mut self,
+        widget: Box<dyn WidgetRef>,
+        area: Rect,
+        z_order: u32,
+    ) -> WidgetId {
+        self.widgets.push_widget(widget, area, z_order)
+    }
+
+    /// Push a widget over the background of the widgets below it, see
+    /// [`WidgetsToDraw::push_blended`].
+    pub fn push_blended(
+        &mut self,
+        widget: Box<dyn WidgetRef>,
+        area: Rect,
+        z_order: u32,
+    ) -> WidgetId {
+        self.widgets.push_blended(widget, area, z_order)
+    }
+
+    /// Push a widget with a `z_order` local to `layer`, see [`layer`](crate::layer).
+    pub fn push_in_layer(
+        &mut self,
+        layer: LayerId,
+        widget: Box<dyn WidgetRef>,
+        area: Rect,
+        local_z_order: u32,
+    ) -> WidgetId {
+        self.widgets
+            .push_widget(widget, area, layer.z_order(local_z_order))
+    }
+
+    /// Push a widget in a rect relative to `parent`, see [`WidgetsToDraw::push_in`].
+    pub fn push_in(
+        &mut self,
+        parent: Rect,
+        relative: Rect,
+        widget: Box<dyn WidgetRef>,
+        z_order: u32,
+    ) -> WidgetId {
+        self.widgets.push_in(parent, relative, widget, z_order)
+    }
+
+    /// Push a closure drawing directly to the buffer this frame, see [`WidgetsToDraw::push_fn`].
+    pub fn push_fn(
+        &mut self,
+        area: Rect,
+        z_order: u32,
+        draw: impl FnOnce(Rect, &mut Buffer) + 'static,
+    ) -> WidgetId {
+        self.widgets.push_fn(area, z_order, draw)
+    }
+
+    /// Push a closure drawing to a buffer scoped to `area`, see [`WidgetsToDraw::with_buffer`].
+    pub fn with_buffer(
+        &mut self,
+        area: Rect,
+        z_order: u32,
+        draw: impl FnMut(&mut Buffer, Rect) + 'static,
+    ) -> WidgetId {
+        self.widgets.with_buffer(area, z_order, draw)
+    }
+
+    /// Push a widget scrolled inside `area`, see [`WidgetsToDraw::push_scrollable`].
+    pub fn push_scrollable(
+        &mut self,
+        widget: Box<dyn WidgetRef>,
+        content_size: Size,
+        offset: Position,
+        area: Rect,
+        z_order: u32,
+    ) -> WidgetId {
+        self.widgets
+            .push_scrollable(widget, content_size, offset, area, z_order)
+    }
+
+    /// Insert a widget rendered every frame until it's removed, see
+    /// [`WidgetsToDraw::insert_persistent`].
+    pub fn insert_persistent(
+        &mut self,
+        id: &'static str,
+        widget: Box<dyn WidgetRef>,
+        area: Rect,
+        z_order: u32,
+    ) {
+        self.widgets.insert_persistent(id, widget, area, z_order);
+        self.request_redraw();
+    }
+
+    pub fn remove_persistent(&mut self, id: &str) {
+        self.widgets.remove_persistent(id);
+        self.request_redraw();
+    }
+
+    /// Render the next frame, when rendering only when needed with
+    /// [`TuiPlugin::render_when_dirty`](crate::TuiPlugin::render_when_dirty). Call it from
+    /// `Update`, or from `PostUpdate` before [`Render`](crate::Render), when something changed on
+    /// screen.
+    pub fn request_redraw(&mut self) {
+        if let Some(redraw) = &mut self.redraw {
+            redraw.set_if_neq(NeedsRedraw(true));
+        }
+    }
+
+    /// Push a stateful widget, rendered with the state stored under `state`.
+    ///
+    /// The state is updated in place during rendering and kept across frames. Nothing is
+    /// pushed if the state was removed.
+    pub fn push_stateful_widget<S: 'static>(
+        &mut self,
+        widget: Box<dyn StatefulWidgetRef<State = S>>,
+        state: WidgetStateId<S>,
+        area: Rect,
+        z_order: u32,
+    ) -> Option<WidgetId> {
+        let state = self.widgets.states.get(&state.id).cloned()?;
+        Some(self.push_widget(Box::new(StatefulWidget { widget, state }), area, z_order))
+    }
+
+    /// Store the state of a stateful widget so that it's kept across frames.
+    pub fn add_widget_state<S: 'static>(&mut self, state: S) -> WidgetStateId<S> {
+        self.widgets.add_widget_state(state)
+    }
+
+    pub fn widget_state_mut<S: 'static>(&self, id: WidgetStateId<S>) -> Option<RefMut<'_, S>> {
+        self.widgets.widget_state_mut(id)
+    }
+
+    /// Push widgets into the named viewport, positioned relative to it. `None` if there's no
+    /// viewport with this name in [`Viewports`].
+    pub fn viewport(&mut self, name: &str) -> Option<ViewportDrawer<'_>> {
+        let area = self.viewports.get(name)?;
+        Some(ViewportDrawer::new(&mut self.widgets, area))
+    }
+
+    /// Clear the whole terminal before rendering the next frame, so it's fully repainted.
+    ///
+    /// Rendering normally only writes the cells that changed since the last frame, so anything
+    /// written to the terminal by something else stays on screen, like after running an editor
+    /// or a glitch of the terminal. This also requests a redraw.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{buffer_to_string, step};
+    /// # use ratatui::{backend::TestBackend, buffer::Cell, widgets::Paragraph};
+    /// #[derive(Resource)]
+    /// struct Scribbled(bool);
+    ///
+    /// fn render(mut drawer: WidgetDrawer<TestBackend>, mut scribbled: ResMut<Scribbled>) {
+    ///     if scribbled.0 {
+    ///         drawer.force_clear();
+    ///         scribbled.0 = false;
+    ///     }
+    ///     drawer.push_widget(Box::new(Paragraph::new("hello")), Rect::new(0, 0, 5, 1), 0);
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(5, 1);
+    /// app.insert_resource(Scribbled(false));
+    /// app.add_systems(Update, render);
+    /// step(&mut app, 1);
+    ///
+    /// // Another program writes over the screen
+    /// let mut terminal = app.world_mut().non_send_resource_mut::<TerminalWrapper<TestBackend>>();
+    /// let cell = Cell::new("X");
+    /// terminal.terminal.backend_mut().draw([(0, 0, &cell), (1, 0, &cell)].into_iter()).unwrap();
+    /// step(&mut app, 1);
+    /// assert_eq!(buffer_to_string(&app), "XXllo");
+    ///
+    /// app.insert_resource(Scribbled(true));
+    /// step(&mut app, 1);
+    /// assert_eq!(buffer_to_string(&app), "hello");
+    /// ```
+    pub fn force_clear(&mut self) {
+        self.widgets.force_clear = true;
+        self.request_redraw();
+    }
+
+    /// Save the frame rendered this frame to the file at `path`, like when a screenshot key is
+    /// pressed.
+    ///
+    /// The file is written once the frame is drawn, in the format of its extension, see
+    /// [`export::save`](crate::export::save). Failures are sent as a [`ScreenshotError`].
+    ///
+    /// It's the buffer ratatui drew, with every backend: the whole screen for the crossterm and
+    /// termwiz backends, only the viewport when rendering inline, and the same buffer as the
+    /// test backends. It's never read back from the terminal, so it doesn't include what other
+    /// programs draw over it. When rendering only when needed with
+    /// [`TuiPlugin::render_when_dirty`](crate::TuiPlugin::render_when_dirty), a redraw is
+    /// requested.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::step;
+    /// # use ratatui::{backend::TestBackend, widgets::Paragraph};
+    /// fn screenshot(mut drawer: WidgetDrawer<TestBackend>) {
+    ///     drawer.push_widget(Box::new(Paragraph::new("hello")), Rect::new(0, 0, 5, 1), 0);
+    ///     drawer.save_screenshot(std::env::temp_dir().join("ratatecs-screenshot.txt"));
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(5, 1);
+    /// app.add_systems(PostUpdate, screenshot.before(Render));
+    /// step(&mut app, 1);
+    /// let path = std::env::temp_dir().join("ratatecs-screenshot.txt");
+    /// assert_eq!(std::fs::read_to_string(path).unwrap(), "hello");
+    /// ```
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::step;
+    /// # use ratatui::backend::TestBackend;
+    /// fn screenshot(mut drawer: WidgetDrawer<TestBackend>) {
+    ///     drawer.save_screenshot("/nonexistent/screenshot.txt");
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(5, 1);
+    /// app.add_systems(PostUpdate, screenshot.before(Render));
+    /// step(&mut app, 1);
+    /// let errors = app.world().resource::<Events<ScreenshotError>>();
+    /// let error = errors.iter_current_update_events().next().unwrap();
+    /// assert_eq!(error.path, std::path::Path::new("/nonexistent/screenshot.txt"));
+    /// assert_eq!(error.error.kind(), std::io::ErrorKind::NotFound);
+    /// ```
+    pub fn save_screenshot(&mut self, path: impl Into<PathBuf>) {
+        self.widgets.screenshots.push(path.into());
+        self.request_redraw();
+    }
+
+    /// Show the terminal cursor at the given position after rendering, until it's hidden again.
+    pub fn set_cursor(&mut self, x: u16, y: u16) {
+        self.cursor.set_if_neq(CursorState {
+            position: Some((x, y)),
+        });
+    }
+
+    pub fn hide_cursor(&mut self) {
+        self.cursor.set_if_neq(CursorState { position: None });
+    }
+
+    /// The area of the terminal to render to, without building a [`Frame`], see [`FrameArea`].
+    pub fn area(&self) -> Rect {
+        self.frame_area.0
+    }
+
+    /// Get the frame of the terminal. Prefer [`WidgetDrawer::area`] when only the area is needed.
+    pub fn get_frame(&mut self) -> Frame<'_> {
+        self.terminal.terminal.get_frame()
+    }
+}
@@ -0,0 +1,293 @@
+//! Conversion of rendered buffers to other formats.
+
+use std::{fs, io, path::Path};
+
+use crossterm::{
+    style::{Attribute, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    Command,
+};
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::color::rgb;
+
+#[cfg(feature = "image-export")]
+mod raster;
+
+#[cfg(feature = "image-export")]
+pub use raster::{to_image, CELL_HEIGHT, CELL_WIDTH};
+
+/// Write a buffer to the file at `path`, in the format of its extension.
+///
+/// `.svg` files are written with [`to_svg`], `.ans` and `.ansi` files with [`to_ansi`], and
+/// with the `image-export` feature, `.png` files with `to_image`. Any other file is written as
+/// plain text with [`to_text`].
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::export::save;
+/// # use ratatui::buffer::Buffer;
+/// let mut buffer = Buffer::empty(Rect::new(0, 0, 5, 1));
+/// buffer.set_string(0, 0, "hello", Style::new().red());
+///
+/// let dir = std::env::temp_dir();
+/// save(&buffer, dir.join("ratatecs-save.txt")).unwrap();
+/// assert_eq!(std::fs::read_to_string(dir.join("ratatecs-save.txt")).unwrap(), "hello");
+/// save(&buffer, dir.join("ratatecs-save.svg")).unwrap();
+/// assert!(std::fs::read_to_string(dir.join("ratatecs-save.svg")).unwrap().starts_with("<svg "));
+/// ```
+pub fn save(buffer: &Buffer, path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("svg") => fs::write(path, to_svg(buffer)),
+        Some("ans" | "ansi") => fs::write(path, to_ansi(buffer)),
+        #[cfg(feature = "image-export")]
+        Some("png") => to_image(buffer).save(path).map_err(io::Error::other),
+        _ => fs::write(path, to_text(buffer)),
+    }
+}
+
+/// Convert a buffer to plain text, one line per row separated by `\n`.
+pub fn to_text(buffer: &Buffer) -> String {
+    let mut output = String::new();
+    for y in 0..buffer.area.height {
+        if y > 0 {
+            output.push('\n');
+        }
+
+        let mut skip = 0usize;
+        for x in 0..buffer.area.width {
+            let cell = &buffer[(buffer.area.x + x, buffer.area.y + y)];
+            // Cells covered by a wide character are not written
+            if skip > 0 || cell.skip {
+                skip = skip.saturating_sub(1);
+                continue;
+            }
+            output.push_str(cell.symbol());
+            skip = cell.symbol().width().saturating_sub(1);
+        }
+    }
+    output
+}
+
+/// Convert a buffer to text with ANSI escape codes for its colors and modifiers.
+///
+/// Lines are separated by `\n`, and the style is reset at the end of each line.
+pub fn to_ansi(buffer: &Buffer) -> String {
+    let mut output = String::new();
+    for y in 0..buffer.area.height {
+        if y > 0 {
+            output.push('\n');
+        }
+
+        let mut style = (Color::Reset, Color::Reset, Modifier::empty());
+        let mut skip = 0usize;
+        for x in 0..buffer.area.width {
+            let cell = &buffer[(buffer.area.x + x, buffer.area.y + y)];
+            // Cells covered by a wide character are not written
+            if skip > 0 || cell.skip {
+                skip = skip.saturating_sub(1);
+                continue;
+            }
+
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if cell_style != style {
+                write_style(&mut output, cell_style.0, cell_style.1, cell_style.2);
+                style = cell_style;
+            }
+            output.push_str(cell.symbol());
+            skip = cell.symbol().width().saturating_sub(1);
+        }
+        if style != (Color::Reset, Color::Reset, Modifier::empty()) {
+            let _ = SetAttribute(Attribute::Reset).write_ansi(&mut output);
+        }
+    }
+    output
+}
+
+/// Width of a cell in the SVGs of [`to_svg`], for a font size of [`SVG_FONT_SIZE`]. Monospace
+/// fonts are usually 0.6 em wide.
+pub const SVG_CELL_WIDTH: f32 = 8.4;
+/// Height of a row in the SVGs of [`to_svg`].
+pub const SVG_LINE_HEIGHT: f32 = 17.0;
+/// Font size of the text in the SVGs of [`to_svg`].
+pub const SVG_FONT_SIZE: f32 = 14.0;
+
+/// Convert a buffer to an SVG image, with its colors and modifiers.
+///
+/// Consecutive cells with the same style are written as a single `<text>` element, stretched
+/// to the width of its cells so that columns line up whatever monospace font is used. The
+/// background colors are drawn as rectangles below the text. Named and indexed colors use the
+/// xterm palette, and the default colors are light gray on black.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::export::to_svg;
+/// # use ratatui::buffer::Buffer;
+/// let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 1));
+/// buffer.set_string(0, 0, "ab", Style::new().red());
+/// buffer.set_string(2, 0, "<c>", Style::new().bold().bg(Color::Indexed(21)));
+///
+/// let svg = to_svg(&buffer);
+/// assert!(svg.starts_with("<svg "));
+/// assert!(svg.contains(r##"fill="#cd0000">ab</text>"##));
+/// assert!(svg.contains(r##"fill="#0000ff"/>"##));
+/// assert!(svg.contains(r##"font-weight="bold">&lt;c&gt;</text>"##));
+/// ```
+pub fn to_svg(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let width = round(f32::from(area.width) * SVG_CELL_WIDTH);
+    let height = round(f32::from(area.height) * SVG_LINE_HEIGHT);
+    let mut backgrounds = String::new();
+    let mut texts = String::new();
+
+    for y in 0..area.height {
+        let top = round(f32::from(y) * SVG_LINE_HEIGHT);
+        let mut x = 0;
+        while x < area.width {
+            // Collect the run of cells with the same style starting at x
+            let first = &buffer[(area.x + x, area.y + y)];
+            let style = (first.fg, first.bg, first.modifier);
+            let start = x;
+            let mut text = String::new();
+            let mut skip = 0usize;
+            while x < area.width {
+                let cell = &buffer[(area.x + x, area.y + y)];
+                if (cell.fg, cell.bg, cell.modifier) != style {
+                    break;
+                }
+                // Cells covered by a wide character are part of the run, but not written
+                if skip == 0 && !cell.skip {
+                    text.push_str(cell.symbol());
+                    skip = cell.symbol().width();
+                }
+                skip = skip.saturating_sub(1);
+                x += 1;
+            }
+
+            let (fg, bg, modifier) = style;
+            let mut fg = rgb(fg).unwrap_or(DEFAULT_FOREGROUND);
+            let mut bg = rgb(bg);
+            if modifier.contains(Modifier::REVERSED) {
+                let reversed_fg = bg.unwrap_or(DEFAULT_BACKGROUND);
+                bg = Some(fg);
+                fg = reversed_fg;
+            }
+            let left = round(f32::from(start) * SVG_CELL_WIDTH);
+            let run_width = round(f32::from(x - start) * SVG_CELL_WIDTH);
+            if let Some(bg) = bg {
+                backgrounds.push_str(&format!(
+                    r#"<rect x="{left}" y="{top}" width="{run_width}" height="{SVG_LINE_HEIGHT}" fill="{}"/>"#,
+                    hex(bg)
+                ));
+                backgrounds.push('\n');
+            }
+            if text.trim().is_empty() || modifier.contains(Modifier::HIDDEN) {
+                continue;
+            }
+
+            let baseline = round(top + SVG_LINE_HEIGHT * 0.8);
+            texts.push_str(&format!(
+                r#"<text x="{left}" y="{baseline}" textLength="{run_width}" fill="{}""#,
+                hex(fg)
+            ));
+            for (flag, attribute) in [
+                (Modifier::BOLD, r#" font-weight="bold""#),
+                (Modifier::DIM, r#" opacity="0.5""#),
+                (Modifier::ITALIC, r#" font-style="italic""#),
+            ] {
+                if modifier.contains(flag) {
+                    texts.push_str(attribute);
+                }
+            }
+            let decorations: Vec<_> = [
+                (Modifier::UNDERLINED, "underline"),
+                (Modifier::CROSSED_OUT, "line-through"),
+            ]
+            .into_iter()
+            .filter(|(flag, _)| modifier.contains(*flag))
+            .map(|(_, decoration)| decoration)
+            .collect();
+            if !decorations.is_empty() {
+                texts.push_str(&format!(r#" text-decoration="{}""#, decorations.join(" ")));
+            }
+            texts.push('>');
+            escape_xml(&mut texts, &text);
+            texts.push_str("</text>\n");
+        }
+    }
+
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" "#,
+            r#"font-family="monospace" font-size="{font_size}" xml:space="preserve">"#,
+            "\n",
+            r#"<rect width="100%" height="100%" fill="{background}"/>"#,
+            "\n{backgrounds}{texts}</svg>\n",
+        ),
+        width = width,
+        height = height,
+        font_size = SVG_FONT_SIZE,
+        background = hex(DEFAULT_BACKGROUND),
+        backgrounds = backgrounds,
+        texts = texts,
+    )
+}
+
+/// Round to a tenth, so coordinates aren't written with float noise like `58.800003`.
+fn round(value: f32) -> f32 {
+    (value * 10.0).round() / 10.0
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape_xml(output: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            _ => output.push(c),
+        }
+    }
+}
+
+fn write_style(output: &mut String, fg: Color, bg: Color, modifier: Modifier) {
+    // Writing to a String can't fail
+    let _ = SetAttribute(Attribute::Reset).write_ansi(output);
+    if fg != Color::Reset {
+        let _ = SetForegroundColor(fg.into()).write_ansi(output);
+    }
+    if bg != Color::Reset {
+        let _ = SetBackgroundColor(bg.into()).write_ansi(output);
+    }
+    for (flag, attribute) in [
+        (Modifier::BOLD, Attribute::Bold),
+        (Modifier::DIM, Attribute::Dim),
+        (Modifier::ITALIC, Attribute::Italic),
+        (Modifier::UNDERLINED, Attribute::Underlined),
+        (Modifier::SLOW_BLINK, Attribute::SlowBlink),
+        (Modifier::RAPID_BLINK, Attribute::RapidBlink),
+        (Modifier::REVERSED, Attribute::Reverse),
+        (Modifier::HIDDEN, Attribute::Hidden),
+        (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+    ] {
+        if modifier.contains(flag) {
+            let _ = SetAttribute(attribute).write_ansi(output);
+        }
+    }
+}
+
+/// The colors used for [`Color::Reset`] in images.
+const DEFAULT_FOREGROUND: [u8; 3] = [229, 229, 229];
+const DEFAULT_BACKGROUND: [u8; 3] = [0, 0, 0];
@@ -0,0 +1,117 @@
+//! Rasterizing buffers to images with a bitmap font.
+
+use font8x8::{
+    UnicodeFonts, BASIC_FONTS, BLOCK_FONTS, BOX_FONTS, GREEK_FONTS, HIRAGANA_FONTS, LATIN_FONTS,
+};
+use image::{Rgba, RgbaImage};
+use ratatui::{
+    buffer::{Buffer, Cell},
+    style::Modifier,
+};
+
+use super::{DEFAULT_BACKGROUND, DEFAULT_FOREGROUND};
+use crate::color::rgb;
+
+/// Width of a cell in pixels, in the images of [`to_image`].
+pub const CELL_WIDTH: u32 = 8;
+/// Height of a cell in pixels, in the images of [`to_image`]. The 8×8 glyphs are stretched
+/// vertically to get the proportions of a terminal cell.
+pub const CELL_HEIGHT: u32 = 16;
+
+/// Render a buffer to an image, with an 8×8 bitmap font, like for screenshots in a README.
+///
+/// Each cell is [`CELL_WIDTH`] by [`CELL_HEIGHT`] pixels, drawn with its foreground and
+/// background colors. Named and indexed colors use the xterm palette, and the default colors are
+/// light gray on black. Bold text is drawn thicker, and dim, reversed, hidden, underlined and
+/// crossed out text are drawn as such. Characters missing from the font, like most CJK
+/// characters and emoji, are left blank.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::export::{to_image, CELL_HEIGHT, CELL_WIDTH};
+/// # use ratatui::buffer::Buffer;
+/// let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 1));
+/// buffer.set_string(0, 0, "ab", Style::new().red().on_blue());
+///
+/// let image = to_image(&buffer);
+/// assert_eq!(image.dimensions(), (3 * CELL_WIDTH, CELL_HEIGHT));
+/// // The corners of the cells are the background
+/// assert_eq!(image.get_pixel(0, 0).0, [0, 0, 238, 255]);
+/// assert_eq!(image.get_pixel(2 * CELL_WIDTH, 0).0, [0, 0, 0, 255]);
+/// ```
+///
+/// Save it as a PNG with [`RgbaImage::save`].
+pub fn to_image(buffer: &Buffer) -> RgbaImage {
+    let area = buffer.area;
+    let mut image = RgbaImage::new(
+        u32::from(area.width) * CELL_WIDTH,
+        u32::from(area.height) * CELL_HEIGHT,
+    );
+    for y in 0..area.height {
+        for x in 0..area.width {
+            let cell = &buffer[(area.x + x, area.y + y)];
+            draw_cell(
+                &mut image,
+                u32::from(x) * CELL_WIDTH,
+                u32::from(y) * CELL_HEIGHT,
+                cell,
+            );
+        }
+    }
+    image
+}
+
+fn draw_cell(image: &mut RgbaImage, left: u32, top: u32, cell: &Cell) {
+    let mut fg = rgb(cell.fg).unwrap_or(DEFAULT_FOREGROUND);
+    let mut bg = rgb(cell.bg).unwrap_or(DEFAULT_BACKGROUND);
+    if cell.modifier.contains(Modifier::REVERSED) {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+    if cell.modifier.contains(Modifier::DIM) {
+        fg = [0, 1, 2].map(|i| ((u16::from(fg[i]) + u16::from(bg[i])) / 2) as u8);
+    }
+    let fg = Rgba([fg[0], fg[1], fg[2], 255]);
+    let bg = Rgba([bg[0], bg[1], bg[2], 255]);
+
+    let mut pixels = [[false; CELL_WIDTH as usize]; CELL_HEIGHT as usize];
+    // Cells covered by a wide character only have their background drawn
+    if !cell.skip && !cell.modifier.contains(Modifier::HIDDEN) {
+        let glyph = cell
+            .symbol()
+            .chars()
+            .next()
+            .and_then(glyph)
+            .unwrap_or_default();
+        let bold = cell.modifier.contains(Modifier::BOLD);
+        for (rows, bits) in pixels.chunks_exact_mut(2).zip(glyph) {
+            // The leftmost pixel is the lowest bit, bold glyphs are smeared one pixel right
+            let bits = if bold { bits | bits << 1 } else { bits };
+            let line: [bool; CELL_WIDTH as usize] =
+                std::array::from_fn(|column| bits & (1 << column) != 0);
+            rows.fill(line);
+        }
+        if cell.modifier.contains(Modifier::UNDERLINED) {
+            pixels[CELL_HEIGHT as usize - 1] = [true; CELL_WIDTH as usize];
+        }
+        if cell.modifier.contains(Modifier::CROSSED_OUT) {
+            pixels[CELL_HEIGHT as usize / 2] = [true; CELL_WIDTH as usize];
+        }
+    }
+
+    for (row, line) in pixels.iter().enumerate() {
+        for (column, set) in line.iter().enumerate() {
+            let color = if *set { fg } else { bg };
+            image.put_pixel(left + column as u32, top + row as u32, color);
+        }
+    }
+}
+
+fn glyph(c: char) -> Option<[u8; 8]> {
+    BASIC_FONTS
+        .get(c)
+        .or_else(|| BOX_FONTS.get(c))
+        .or_else(|| BLOCK_FONTS.get(c))
+        .or_else(|| LATIN_FONTS.get(c))
+        .or_else(|| GREEK_FONTS.get(c))
+        .or_else(|| HIRAGANA_FONTS.get(c))
+}
@@ -0,0 +1,286 @@
+//! Keyboard focus shared between panels.
+//!
+//! Panels register an id with [`AppExt::add_focusable`](crate::AppExt::add_focusable), and run
+//! their input systems with the [`has_focus`] condition so only the focused panel reacts to keys.
+//! Add [`cycle_focus`] to move the focus with Tab and Shift+Tab, or [`focus_nav`] to also move
+//! it with the arrow keys between [`Focusable`] entities.
+//!
+//! ```rust,no_run
+//! use ratatecs::prelude::*;
+//!
+//! fn counter_input(mut keys: EventReader<KeyPressed>) {
+//!     for KeyPressed(key_event) in keys.read() {
+//!         // ...
+//!     }
+//! }
+//!
+//! App::new()
+//!     .add_plugins(RatatEcsPlugins::new())
+//!     .add_focusable("counter")
+//!     .add_focusable("progress")
+//!     .add_systems(Update, (cycle_focus, counter_input.run_if(has_focus("counter"))))
+//!     .run();
+//! ```
+
+use std::collections::HashMap;
+
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    event::EventReader,
+    query::Added,
+    removal_detection::RemovedComponents,
+    system::{Local, Query, Res, ResMut, Resource},
+};
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+
+use crate::{input::KeyPressed, layout::PanelArea};
+
+/// The focusable panels, and the one currently focused.
+///
+/// The first registered panel starts focused.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Focus {
+    order: Vec<&'static str>,
+    current: Option<usize>,
+}
+
+impl Focus {
+    /// Add a focusable panel after the previously registered ones. Registering an id twice does
+    /// nothing.
+    pub fn register(&mut self, id: &'static str) {
+        if self.order.contains(&id) {
+            return;
+        }
+        self.order.push(id);
+        if self.current.is_none() {
+            self.current = Some(0);
+        }
+    }
+
+    /// Remove a panel. If it was focused, the panel registered after it is focused instead,
+    /// wrapping around.
+    pub fn unregister(&mut self, id: &str) {
+        let Some(index) = self.order.iter().position(|registered| *registered == id) else {
+            return;
+        };
+        self.order.remove(index);
+        self.current = match self.current {
+            _ if self.order.is_empty() => None,
+            Some(current) if current > index => Some(current - 1),
+            Some(current) if current == index => Some(index % self.order.len()),
+            current => current,
+        };
+    }
+
+    /// The id of the focused panel.
+    pub fn focused(&self) -> Option<&'static str> {
+        self.current.map(|index| self.order[index])
+    }
+
+    pub fn is_focused(&self, id: &str) -> bool {
+        self.focused() == Some(id)
+    }
+
+    /// Focus the panel, if it was registered.
+    pub fn set(&mut self, id: &str) {
+        if let Some(index) = self.order.iter().position(|registered| *registered == id) {
+            self.current = Some(index);
+        }
+    }
+
+    /// Focus the panel registered after the focused one, wrapping around.
+    pub fn next(&mut self) {
+        if let Some(current) = self.current {
+            self.current = Some((current + 1) % self.order.len());
+        }
+    }
+
+    /// Focus the panel registered before the focused one, wrapping around.
+    pub fn previous(&mut self) {
+        if let Some(current) = self.current {
+            self.current = Some((current + self.order.len() - 1) % self.order.len());
+        }
+    }
+}
+
+/// Run condition true when the panel `id` is focused.
+///
+/// Systems skipped while unfocused don't consume their events, so events read through an
+/// `EventReader` may still be seen right after gaining focus if they were sent during the
+/// previous frame.
+pub fn has_focus(id: &'static str) -> impl FnMut(Res<Focus>) -> bool + Clone {
+    move |focus: Res<Focus>| focus.is_focused(id)
+}
+
+/// Marks an entity, like a field of a form, as focusable with the id `0`.
+///
+/// Focusable entities are registered in [`Focus`] when spawned, in the order they're spawned,
+/// like with [`AppExt::add_focusable`](crate::AppExt::add_focusable), and unregistered when
+/// despawned or when the component is removed. Give them a [`PanelArea`] to move the focus
+/// between them with the arrow keys, see [`focus_nav`].
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// let mut app = App::new_tui_test(20, 10);
+/// app.add_systems(Update, cycle_focus);
+/// let [_, email, _] = ["name", "email", "submit"].map(|id| {
+///     app.world_mut().spawn(Focusable(id)).id()
+/// });
+/// step(&mut app, 1);
+/// app.world_mut().resource_mut::<Focus>().set("email");
+///
+/// // The next field takes the focus when the focused one goes away
+/// app.world_mut().despawn(email);
+/// step(&mut app, 1);
+/// assert_eq!(app.world().resource::<Focus>().focused(), Some("submit"));
+///
+/// app.send_test_event(event::Event::Key(event::KeyCode::Tab.into()));
+/// step(&mut app, 2);
+/// assert_eq!(app.world().resource::<Focus>().focused(), Some("name"));
+/// ```
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Focusable(pub &'static str);
+
+pub(crate) fn register_focusables(
+    added: Query<(Entity, &Focusable), Added<Focusable>>,
+    focusables: Query<&Focusable>,
+    mut removed: RemovedComponents<Focusable>,
+    mut registered: Local<HashMap<Entity, &'static str>>,
+    mut focus: ResMut<Focus>,
+) {
+    for entity in removed.read() {
+        let Some(id) = registered.remove(&entity) else {
+            continue;
+        };
+        // Another entity can still use the same id
+        if !focusables.iter().any(|focusable| focusable.0 == id) {
+            focus.unregister(id);
+        }
+    }
+    for (entity, focusable) in &added {
+        registered.insert(entity, focusable.0);
+        focus.register(focusable.0);
+    }
+}
+
+/// Move the focus like [`cycle_focus`] on Tab and Shift+Tab, and with the arrow keys to the
+/// closest [`Focusable`] in that direction, from the [`PanelArea`]s of the entities.
+///
+/// The focus doesn't move when there is no focusable entity in that direction. Panels already
+/// using the arrow keys should use [`cycle_focus`] instead.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// let mut app = App::new_tui_test(20, 10);
+/// app.add_systems(Update, focus_nav);
+/// // "name" and "email" side by side, "submit" below "name"
+/// for (id, area) in [
+///     ("name", Rect::new(0, 0, 10, 3)),
+///     ("email", Rect::new(10, 0, 10, 3)),
+///     ("submit", Rect::new(0, 3, 10, 1)),
+/// ] {
+///     app.world_mut().spawn((Focusable(id), PanelArea(area)));
+/// }
+///
+/// let press = |app: &mut App, code: event::KeyCode| {
+///     app.send_test_event(event::Event::Key(code.into()));
+///     step(app, 2);
+///     app.world().resource::<Focus>().focused()
+/// };
+/// assert_eq!(press(&mut app, event::KeyCode::Right), Some("email"));
+/// assert_eq!(press(&mut app, event::KeyCode::Down), Some("submit"));
+/// assert_eq!(press(&mut app, event::KeyCode::Down), Some("submit"));
+/// assert_eq!(press(&mut app, event::KeyCode::Tab), Some("name"));
+/// ```
+pub fn focus_nav(
+    mut focus: ResMut<Focus>,
+    mut keys: EventReader<KeyPressed>,
+    focusables: Query<(&Focusable, &PanelArea)>,
+) {
+    for KeyPressed(key_event) in keys.read() {
+        let direction = match key_event.code {
+            KeyCode::Tab => {
+                focus.next();
+                continue;
+            }
+            KeyCode::BackTab => {
+                focus.previous();
+                continue;
+            }
+            KeyCode::Left => Direction::Left,
+            KeyCode::Right => Direction::Right,
+            KeyCode::Up => Direction::Up,
+            KeyCode::Down => Direction::Down,
+            _ => continue,
+        };
+        let Some(current) = focus.focused().and_then(|focused| {
+            focusables
+                .iter()
+                .find(|(focusable, _)| focusable.0 == focused)
+                .map(|(_, area)| area.0)
+        }) else {
+            continue;
+        };
+        let closest = focusables
+            .iter()
+            .filter_map(|(focusable, area)| {
+                direction
+                    .distance(current, area.0)
+                    .map(|distance| (distance, focusable.0))
+            })
+            .min_by_key(|(distance, _)| *distance);
+        if let Some((_, id)) = closest {
+            focus.set(id);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// How far `to` is from `from` in this direction, or `None` if it isn't entirely past `from`
+    /// in this direction. Moving sideways counts for half as much as moving in the direction.
+    fn distance(self, from: Rect, to: Rect) -> Option<u32> {
+        let (gap, sideways) = match self {
+            Direction::Left => (from.left().checked_sub(to.right())?, center_y(from, to)),
+            Direction::Right => (to.left().checked_sub(from.right())?, center_y(from, to)),
+            Direction::Up => (from.top().checked_sub(to.bottom())?, center_x(from, to)),
+            Direction::Down => (to.top().checked_sub(from.bottom())?, center_x(from, to)),
+        };
+        // The centers are doubled to stay in integers, so is the gap
+        Some(u32::from(gap) * 4 + sideways)
+    }
+}
+
+/// The distance between the doubled horizontal centers of the rects.
+fn center_x(a: Rect, b: Rect) -> u32 {
+    let center = |rect: Rect| u32::from(rect.x) * 2 + u32::from(rect.width);
+    center(a).abs_diff(center(b))
+}
+
+/// The distance between the doubled vertical centers of the rects.
+fn center_y(a: Rect, b: Rect) -> u32 {
+    let center = |rect: Rect| u32::from(rect.y) * 2 + u32::from(rect.height);
+    center(a).abs_diff(center(b))
+}
+
+/// Move the focus to the next panel on Tab, and to the previous one on Shift+Tab.
+pub fn cycle_focus(mut focus: ResMut<Focus>, mut keys: EventReader<KeyPressed>) {
+    for KeyPressed(key_event) in keys.read() {
+        match key_event.code {
+            KeyCode::Tab => focus.next(),
+            KeyCode::BackTab => focus.previous(),
+            _ => (),
+        }
+    }
+}
@@ -0,0 +1,377 @@
+//! Bevy events translated from the backend events.
+
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    event::{Event, EventWriter},
+    schedule::SystemSet,
+    system::{Res, ResMut, Resource},
+};
+use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyEventKind, KeyModifiers};
+
+use crate::{action::KeyBinding, BackendEvent, BackendEvents};
+
+/// Any event received from the terminal, sent once for each event in [`BackendEvents`].
+///
+/// Unlike checking [`BackendEvent`] or [`BackendEvents`], reading these
+/// with an `EventReader` sees each event exactly once per system, whichever schedule it runs in.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// #[derive(Resource, Default)]
+/// struct Presses(u32);
+///
+/// fn count(mut events: EventReader<TerminalEvent>, mut presses: ResMut<Presses>) {
+///     presses.0 += events.read().count() as u32;
+/// }
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.init_resource::<Presses>();
+/// // Also in `Last`, where the terminal events are read
+/// app.add_systems(Update, count).add_systems(Last, count);
+///
+/// app.send_test_event(event::Event::Key(event::KeyCode::Enter.into()));
+/// step(&mut app, 3);
+/// // Once for each system
+/// assert_eq!(app.world().resource::<Presses>().0, 2);
+/// ```
+#[derive(Event, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TerminalEvent(pub CrosstermEvent);
+
+/// A key event received from the terminal.
+#[derive(Event, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyEvent(pub crossterm::event::KeyEvent);
+
+/// A key was pressed, or is repeating while held down.
+///
+/// Unlike [`KeyEvent`], releasing the key isn't reported, so actions don't fire twice on
+/// terminals reporting releases.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// use event::{KeyCode, KeyEventKind, KeyModifiers};
+///
+/// #[derive(Resource, Default)]
+/// struct Actions(u32);
+///
+/// fn act(mut actions: ResMut<Actions>, mut keys: EventReader<KeyPressed>) {
+///     actions.0 += keys.read().count() as u32;
+/// }
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.init_resource::<Actions>();
+/// app.add_systems(Update, act);
+/// for kind in [KeyEventKind::Press, KeyEventKind::Release] {
+///     let key = event::KeyEvent::new_with_kind(KeyCode::Enter, KeyModifiers::NONE, kind);
+///     app.send_test_event(event::Event::Key(key));
+/// }
+/// step(&mut app, 3);
+/// assert_eq!(app.world().resource::<Actions>().0, 1);
+/// assert!(is_key_press(&event::KeyCode::Enter.into()));
+/// ```
+#[derive(Event, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyPressed(pub crossterm::event::KeyEvent);
+
+/// A key was released. Only reported by some terminals, like on Windows or with the kitty
+/// keyboard protocol.
+#[derive(Event, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeyReleased(pub crossterm::event::KeyEvent);
+
+/// Whether the key event is a press or a repeat, rather than a release.
+///
+/// Terminals reporting releases send two events for a single key press, check this before
+/// acting on a key to not do it twice.
+pub fn is_key_press(key: &crossterm::event::KeyEvent) -> bool {
+    matches!(key.kind, KeyEventKind::Press | KeyEventKind::Repeat)
+}
+
+/// The key pressed, if the event of the last frame in [`BackendEvent`] is a key press, with its
+/// modifiers.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// use event::{KeyCode, KeyModifiers};
+///
+/// fn shortcuts(event: Res<BackendEvent>) {
+///     let Some(key) = key_pressed(&event) else {
+///         return;
+///     };
+///     if key.code == KeyCode::Char('s') && key.modifiers == KeyModifiers::CONTROL {
+///         // Save
+///     }
+/// }
+///
+/// let typed = BackendEvent(Some(event::Event::Key(KeyCode::Char('a').into())));
+/// assert_eq!(char_pressed(&typed), Some('a'));
+/// assert_eq!(key_pressed(&BackendEvent(None)), None);
+/// ```
+pub fn key_pressed(event: &BackendEvent) -> Option<crossterm::event::KeyEvent> {
+    match &event.0 {
+        Some(CrosstermEvent::Key(key_event)) if is_key_press(key_event) => Some(*key_event),
+        _ => None,
+    }
+}
+
+/// The character typed, if the event of the last frame in [`BackendEvent`] is the press of a
+/// character key. Shortcuts with Ctrl or Alt are included, check [`key_pressed`] to tell them
+/// apart.
+pub fn char_pressed(event: &BackendEvent) -> Option<char> {
+    match key_pressed(event)?.code {
+        KeyCode::Char(c) => Some(c),
+        _ => None,
+    }
+}
+
+/// Run condition true when a key was pressed during the last frame.
+pub fn on_any_key() -> impl FnMut(Res<BackendEvents>) -> bool + Clone {
+    move |events: Res<BackendEvents>| pressed_keys(&events).next().is_some()
+}
+
+/// Run condition true when `code` was pressed during the last frame, whatever modifiers were
+/// held.
+///
+/// ```rust,no_run
+/// # use ratatecs::prelude::*;
+/// fn exit(mut exit: EventWriter<AppExit>) {
+///     exit.send(AppExit::Success);
+/// }
+///
+/// App::new()
+///     .add_plugins(RatatEcsPlugins::new())
+///     .add_systems(Update, exit.run_if(on_key(event::KeyCode::Esc)))
+///     .run();
+/// ```
+pub fn on_key(code: KeyCode) -> impl FnMut(Res<BackendEvents>) -> bool + Clone {
+    move |events: Res<BackendEvents>| pressed_keys(&events).any(|key| key.code == code)
+}
+
+/// Run condition true when `code` was pressed with exactly `modifiers` held during the last
+/// frame, like Ctrl+C. Matches like a [`KeyBinding`].
+pub fn on_key_mods(
+    code: KeyCode,
+    modifiers: KeyModifiers,
+) -> impl FnMut(Res<BackendEvents>) -> bool + Clone {
+    let binding = KeyBinding::new(code, modifiers);
+    move |events: Res<BackendEvents>| pressed_keys(&events).any(|key| binding.matches(key))
+}
+
+fn pressed_keys(events: &BackendEvents) -> impl Iterator<Item = &crossterm::event::KeyEvent> {
+    events.0.iter().filter_map(|event| match event {
+        CrosstermEvent::Key(key_event) if is_key_press(key_event) => Some(key_event),
+        _ => None,
+    })
+}
+
+/// A mouse event received from the terminal.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MouseEvent(pub crossterm::event::MouseEvent);
+
+/// The terminal was resized to the given width and height.
+///
+/// One is sent for every resize reported by the terminal, which can be many per frame while a
+/// window is being resized. To only react to the final size, watch
+/// [`TerminalSize`](crate::TerminalSize) instead.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResizeEvent {
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Text pasted into the terminal.
+#[derive(Event, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PasteEvent(pub String);
+
+/// Systems handling app-wide shortcuts, like quitting or opening a help overlay, that see the
+/// events of the last frame before the panels.
+///
+/// Add them to this set in [`PreUpdate`](bevy_app::PreUpdate), before the panels in
+/// [`Update`](bevy_app::Update), and [`consume`](InputEvent::consume) the events they handle
+/// in [`InputEvents`] so that the panels reading [`InputEvents::unconsumed`] skip them. Other
+/// ways to read events, like [`BackendEvents`] or [`KeyPressed`], still see consumed events.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// #[derive(Resource, Default)]
+/// struct Help(bool);
+///
+/// #[derive(Resource, Default)]
+/// struct Typed(String);
+///
+/// fn toggle_help(mut events: ResMut<InputEvents>, mut help: ResMut<Help>) {
+///     for event in events.unconsumed_mut() {
+///         if let event::Event::Key(key) = &event.event {
+///             if key.code == event::KeyCode::Char('?') {
+///                 help.0 = !help.0;
+///                 event.consume();
+///             }
+///         }
+///     }
+/// }
+///
+/// fn editor(events: Res<InputEvents>, mut typed: ResMut<Typed>) {
+///     for key in events.pressed_keys() {
+///         if let event::KeyCode::Char(c) = key.code {
+///             typed.0.push(c);
+///         }
+///     }
+/// }
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.init_resource::<Help>()
+///     .init_resource::<Typed>()
+///     .add_systems(PreUpdate, toggle_help.in_set(GlobalInput))
+///     .add_systems(Update, editor);
+///
+/// for c in ['a', '?', 'b'] {
+///     app.send_test_event(event::Event::Key(event::KeyCode::Char(c).into()));
+/// }
+/// step(&mut app, 2);
+/// assert!(app.world().resource::<Help>().0);
+/// assert_eq!(app.world().resource::<Typed>().0, "ab");
+/// ```
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GlobalInput;
+
+/// An event received from the terminal, that a system can consume so that the systems running
+/// after it skip it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputEvent {
+    pub event: CrosstermEvent,
+    /// Whether a system already handled the event, see [`InputEvent::consume`].
+    pub consumed: bool,
+}
+
+impl InputEvent {
+    /// Mark the event as handled, so the systems running after this one skip it.
+    pub fn consume(&mut self) {
+        self.consumed = true;
+    }
+}
+
+/// The events received from the terminal during the last frame, that systems can consume, like
+/// the [`GlobalInput`] systems running before the panels.
+///
+/// Filled at the start of each frame with the events of [`BackendEvents`], none consumed.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct InputEvents(pub Vec<InputEvent>);
+
+impl InputEvents {
+    /// The events no system consumed yet.
+    pub fn unconsumed(&self) -> impl Iterator<Item = &CrosstermEvent> {
+        self.0
+            .iter()
+            .filter(|event| !event.consumed)
+            .map(|event| &event.event)
+    }
+
+    /// The events no system consumed yet, to consume some of them.
+    pub fn unconsumed_mut(&mut self) -> impl Iterator<Item = &mut InputEvent> {
+        self.0.iter_mut().filter(|event| !event.consumed)
+    }
+
+    /// The keys pressed that no system consumed yet, like with [`is_key_press`].
+    pub fn pressed_keys(&self) -> impl Iterator<Item = &crossterm::event::KeyEvent> {
+        self.unconsumed().filter_map(|event| match event {
+            CrosstermEvent::Key(key_event) if is_key_press(key_event) => Some(key_event),
+            _ => None,
+        })
+    }
+}
+
+pub(crate) fn update_input_events(events: Res<BackendEvents>, mut input: ResMut<InputEvents>) {
+    if events.0.is_empty() && input.0.is_empty() {
+        return;
+    }
+    input.0.clear();
+    input
+        .0
+        .extend(events.0.iter().cloned().map(|event| InputEvent {
+            event,
+            consumed: false,
+        }));
+}
+
+/// Whether the terminal has the focus, as of the last frame.
+///
+/// Only updated when focus reporting is enabled with
+/// [`TuiPlugin::enable_focus_change`](crate::TuiPlugin::enable_focus_change), and supported by the
+/// terminal. The terminal is assumed focused until it reports otherwise.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// let mut app = App::new_tui_test(10, 1);
+/// app.send_test_event(event::Event::FocusLost);
+/// step(&mut app, 1);
+/// assert!(!app.world().resource::<TerminalFocus>().focused);
+///
+/// app.send_test_event(event::Event::FocusGained);
+/// step(&mut app, 1);
+/// assert!(app.world().resource::<TerminalFocus>().focused);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalFocus {
+    pub focused: bool,
+}
+
+impl Default for TerminalFocus {
+    fn default() -> Self {
+        Self { focused: true }
+    }
+}
+
+pub(crate) fn update_terminal_focus(events: Res<BackendEvents>, mut focus: ResMut<TerminalFocus>) {
+    let focused = events.0.iter().rev().find_map(|event| match event {
+        CrosstermEvent::FocusGained => Some(true),
+        CrosstermEvent::FocusLost => Some(false),
+        _ => None,
+    });
+    if let Some(focused) = focused {
+        focus.set_if_neq(TerminalFocus { focused });
+    }
+}
+
+pub(crate) fn send_terminal_events(
+    events: Res<BackendEvents>,
+    mut terminal_events: EventWriter<TerminalEvent>,
+) {
+    terminal_events.send_batch(events.0.iter().cloned().map(TerminalEvent));
+}
+
+pub(crate) fn send_input_events(
+    events: Res<BackendEvents>,
+    mut keys: EventWriter<KeyEvent>,
+    mut pressed: EventWriter<KeyPressed>,
+    mut released: EventWriter<KeyReleased>,
+    mut mouse: EventWriter<MouseEvent>,
+    mut resizes: EventWriter<ResizeEvent>,
+    mut pastes: EventWriter<PasteEvent>,
+) {
+    for event in &events.0 {
+        match event {
+            CrosstermEvent::Key(key_event) => {
+                keys.send(KeyEvent(*key_event));
+                if is_key_press(key_event) {
+                    pressed.send(KeyPressed(*key_event));
+                } else {
+                    released.send(KeyReleased(*key_event));
+                }
+            }
+            CrosstermEvent::Mouse(mouse_event) => {
+                mouse.send(MouseEvent(*mouse_event));
+            }
+            CrosstermEvent::Resize(width, height) => {
+                resizes.send(ResizeEvent {
+                    width: *width,
+                    height: *height,
+                });
+            }
+            CrosstermEvent::Paste(text) => {
+                pastes.send(PasteEvent(text.clone()));
+            }
+            CrosstermEvent::FocusGained | CrosstermEvent::FocusLost => (),
+        }
+    }
+}
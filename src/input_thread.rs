@@ -0,0 +1,104 @@
+//! Reading terminal events on a background thread, see [`TuiPlugin::threaded_input`].
+//!
+//! [`TuiPlugin::threaded_input`]: crate::TuiPlugin::threaded_input
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use bevy_ecs::system::{Res, ResMut, Resource};
+use crossterm::event::Event;
+
+use crate::{BackendEvents, PollConfig};
+
+/// How often the thread checks if it should stop while no event arrives.
+const SHUTDOWN_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Resource)]
+pub(crate) struct InputThread {
+    receiver: Mutex<Receiver<Event>>,
+    shutdown: Arc<AtomicBool>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl InputThread {
+    pub(crate) fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let handle = thread::Builder::new()
+            .name("ratatecs input".to_string())
+            .spawn({
+                let shutdown = shutdown.clone();
+                move || {
+                    while !shutdown.load(Ordering::Relaxed) {
+                        if !crossterm::event::poll(SHUTDOWN_CHECK_INTERVAL).unwrap_or(false) {
+                            continue;
+                        }
+                        let Ok(event) = crossterm::event::read() else {
+                            break;
+                        };
+                        if sender.send(event).is_err() {
+                            break;
+                        }
+                    }
+                }
+            })
+            .expect("failed to spawn the input thread");
+
+        Self {
+            receiver: Mutex::new(receiver),
+            shutdown,
+            handle: Mutex::new(Some(handle)),
+        }
+    }
+
+    /// Stop the thread and wait for it, so it doesn't read the terminal once it's restored.
+    pub(crate) fn stop(&self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        let handle = self.handle.lock().ok().and_then(|mut handle| handle.take());
+        if let Some(handle) = handle {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for InputThread {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Wait for the first event up to the poll window like when polling on the main thread, but
+/// wake up as soon as it arrives, then take the other pending events.
+pub(crate) fn get_threaded_events(
+    config: Res<PollConfig>,
+    thread: Res<InputThread>,
+    mut events: ResMut<BackendEvents>,
+) {
+    events.0.clear();
+
+    let Ok(receiver) = thread.receiver.lock() else {
+        return;
+    };
+    match receiver.recv_timeout(config.timeout) {
+        Ok(event) => events.0.push(event),
+        Err(RecvTimeoutError::Timeout) => return,
+        // The thread stopped after failing to read the terminal, wait like polling would instead
+        // of returning right away every frame
+        Err(RecvTimeoutError::Disconnected) => {
+            thread::sleep(config.timeout);
+            return;
+        }
+    }
+    events.0.extend(receiver.try_iter());
+}
+
+pub(crate) fn stop_on_exit(thread: Res<InputThread>) {
+    thread.stop();
+}
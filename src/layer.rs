@@ -0,0 +1,78 @@
+//! Layers of `z_order`s, so panels don't have to agree on global `z_order`s.
+//!
+//! Each panel registers a layer with [`AppExt::add_layer`](crate::AppExt::add_layer), and pushes
+//! its widgets with a `z_order` local to the layer, with
+//! [`WidgetDrawer::push_in_layer`](crate::WidgetDrawer::push_in_layer). Layers are stacked in
+//! the order they were registered: every widget of a layer is rendered above the widgets of the
+//! layers registered before it. Widgets pushed outside of a layer, with a `z_order` below
+//! [`LayerId::SIZE`], are rendered below all the layers.
+//!
+//! ```rust
+//! # use ratatecs::prelude::*;
+//! # use ratatecs::testing::{assert_buffer_contains, step};
+//! # use ratatui::{backend::TestBackend, widgets::Paragraph};
+//! fn background(mut drawer: WidgetDrawer<TestBackend>, layers: Res<LayerRegistry>) {
+//!     let layer = layers.get("background").unwrap();
+//!     let text = Paragraph::new("background");
+//!     drawer.push_in_layer(layer, Box::new(text), Rect::new(0, 0, 10, 1), 999);
+//! }
+//!
+//! fn popup(mut drawer: WidgetDrawer<TestBackend>, layers: Res<LayerRegistry>) {
+//!     let layer = layers.get("popup").unwrap();
+//!     drawer.push_in_layer(layer, Box::new(Paragraph::new("pop")), Rect::new(0, 0, 3, 1), 0);
+//! }
+//!
+//! let mut app = App::new_tui_test(10, 1);
+//! app.add_layer("background").add_layer("popup");
+//! // The popup is pushed first, and below in its own layer, but still ends up on top
+//! app.add_systems(Update, (popup, background).chain());
+//! step(&mut app, 1);
+//! assert_buffer_contains(&app, "popkground");
+//! ```
+
+use bevy_ecs::system::Resource;
+
+/// A registered layer, see [`LayerRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LayerId(u32);
+
+impl LayerId {
+    /// Number of `z_order`s in each layer.
+    pub const SIZE: u32 = 1000;
+
+    /// The lowest `z_order` of the layer.
+    pub fn base(self) -> u32 {
+        // The first range is left to the widgets pushed outside of a layer
+        self.0.saturating_add(1).saturating_mul(Self::SIZE)
+    }
+
+    /// The global `z_order` of `local_z_order` in this layer. `z_order`s past the size of the
+    /// layer stay at the top of the layer, below the next layers.
+    pub fn z_order(self, local_z_order: u32) -> u32 {
+        self.base()
+            .saturating_add(local_z_order.min(Self::SIZE - 1))
+    }
+}
+
+/// The registered layers, from the bottom one to the top one.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct LayerRegistry {
+    layers: Vec<&'static str>,
+}
+
+impl LayerRegistry {
+    /// Add a layer above the previously registered ones. Registering a name twice returns the
+    /// existing layer.
+    pub fn register(&mut self, name: &'static str) -> LayerId {
+        if let Some(layer) = self.get(name) {
+            return layer;
+        }
+        self.layers.push(name);
+        LayerId(self.layers.len() as u32 - 1)
+    }
+
+    pub fn get(&self, name: &str) -> Option<LayerId> {
+        let index = self.layers.iter().position(|layer| *layer == name)?;
+        Some(LayerId(index as u32))
+    }
+}
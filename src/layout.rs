@@ -0,0 +1,306 @@
+//! Helpers to compute the area of widgets.
+//!
+//! Besides the helpers splitting rects, layouts can be registered once with
+//! [`AppExt::add_layout`](crate::AppExt::add_layout), and their resolved areas read by name from
+//! [`LayoutRects`] by every panel.
+
+use std::collections::HashMap;
+
+use bevy_ecs::{
+    change_detection::DetectChanges,
+    component::Component,
+    system::{Res, ResMut, Resource},
+};
+use ratatui::layout::{Layout, Rect};
+
+use crate::FrameArea;
+
+/// A layout splitting the terminal, or a named area, into named areas.
+#[derive(Debug, Clone)]
+struct Split {
+    parent: Option<&'static str>,
+    layout: Layout,
+    names: Vec<&'static str>,
+}
+
+/// The layouts registered with [`AppExt::add_layout`](crate::AppExt::add_layout) and
+/// [`AppExt::add_layout_in`](crate::AppExt::add_layout_in), in the order they're resolved.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Layouts {
+    splits: Vec<Split>,
+}
+
+impl Layouts {
+    /// Split the whole terminal with `layout`, naming the areas in order.
+    pub fn add(&mut self, layout: Layout, names: impl IntoIterator<Item = &'static str>) {
+        self.splits.push(Split {
+            parent: None,
+            layout,
+            names: names.into_iter().collect(),
+        });
+    }
+
+    /// Split the area named `parent` with `layout`, naming the areas in order. The parent must
+    /// come from a layout added before this one.
+    pub fn add_in(
+        &mut self,
+        parent: &'static str,
+        layout: Layout,
+        names: impl IntoIterator<Item = &'static str>,
+    ) {
+        self.splits.push(Split {
+            parent: Some(parent),
+            layout,
+            names: names.into_iter().collect(),
+        });
+    }
+}
+
+/// The areas of the registered [`Layouts`], by name, for the current size of the terminal.
+///
+/// They're computed before [`Update`](bevy_app::Update), again whenever the terminal is resized
+/// or the layouts change. The whole terminal is the [`FrameArea`].
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// # use ratatui::backend::TestBackend;
+/// let mut app = App::new_tui_test(20, 10);
+/// app.add_layout(
+///     Layout::horizontal([Constraint::Length(5), Constraint::Fill(1)]).margin(1),
+///     ["side", "main"],
+/// );
+/// step(&mut app, 1);
+/// let rects = app.world().resource::<LayoutRects>();
+/// assert_eq!(rects.get("side"), Some(Rect::new(1, 1, 5, 8)));
+/// assert_eq!(rects.get("main"), Some(Rect::new(6, 1, 13, 8)));
+///
+/// app.world_mut()
+///     .non_send_resource_mut::<TerminalWrapper<TestBackend>>()
+///     .terminal
+///     .backend_mut()
+///     .resize(40, 10);
+/// step(&mut app, 2);
+/// let rects = app.world().resource::<LayoutRects>();
+/// assert_eq!(rects.get("main"), Some(Rect::new(6, 1, 33, 8)));
+/// ```
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct LayoutRects {
+    rects: HashMap<&'static str, Rect>,
+}
+
+impl LayoutRects {
+    /// The area named `name`, if a layout has an area with this name.
+    pub fn get(&self, name: &str) -> Option<Rect> {
+        self.rects.get(name).copied()
+    }
+}
+
+pub(crate) fn resolve_layouts(
+    frame_area: Res<FrameArea>,
+    layouts: Res<Layouts>,
+    mut rects: ResMut<LayoutRects>,
+) {
+    if !frame_area.is_changed() && !layouts.is_changed() {
+        return;
+    }
+
+    let mut resolved = HashMap::new();
+    let terminal = frame_area.0;
+    for split in &layouts.splits {
+        let parent = match split.parent {
+            Some(parent) => resolved.get(parent).copied().unwrap_or_default(),
+            None => terminal,
+        };
+        let areas = split.layout.split(parent);
+        resolved.extend(split.names.iter().copied().zip(areas.iter().copied()));
+    }
+    rects.rects = resolved;
+}
+
+/// The area a parent panel gives to a child panel, attached to the entity of the child.
+///
+/// The parent splits its own area and sets the area of each child, which draws in it without
+/// knowing where it is on screen, like with [`WidgetsToDraw::push_in`](crate::WidgetsToDraw::push_in)
+/// and rects relative to it.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{snapshot, step};
+/// # use ratatui::widgets::Paragraph;
+/// #[derive(Component)]
+/// struct Label(&'static str);
+///
+/// fn parent(mut children: Query<&mut PanelArea, With<Label>>) {
+///     let areas = Layout::horizontal([Constraint::Length(4); 2]).split(Rect::new(1, 0, 8, 1));
+///     for (mut area, rect) in children.iter_mut().zip(areas.iter()) {
+///         area.0 = *rect;
+///     }
+/// }
+///
+/// fn child(children: Query<(&Label, &PanelArea)>, mut widgets: NonSendMut<WidgetsToDraw>) {
+///     for (label, area) in &children {
+///         let text = Box::new(Paragraph::new(label.0));
+///         widgets.push_in(area.0, Rect::new(1, 0, 3, 1), text, 0);
+///     }
+/// }
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.world_mut().spawn((Label("ab"), PanelArea::default()));
+/// app.world_mut().spawn((Label("cd"), PanelArea::default()));
+/// app.add_systems(Update, (parent, child).chain());
+/// step(&mut app, 1);
+/// assert_eq!(snapshot(&app), "  ab  cd");
+/// ```
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PanelArea(pub Rect);
+
+/// The rect at `relative`, relative to the top left corner of `parent`, clipped to `parent`.
+///
+/// ```rust
+/// # use ratatui::layout::Rect;
+/// # use ratatecs::layout::offset_in;
+/// let parent = Rect::new(10, 5, 20, 4);
+/// assert_eq!(offset_in(parent, Rect::new(2, 1, 5, 1)), Rect::new(12, 6, 5, 1));
+/// assert_eq!(offset_in(parent, Rect::new(15, 2, 10, 10)), Rect::new(25, 7, 5, 2));
+/// ```
+pub fn offset_in(parent: Rect, relative: Rect) -> Rect {
+    Rect {
+        x: parent.x.saturating_add(relative.x),
+        y: parent.y.saturating_add(relative.y),
+        ..relative
+    }
+    .intersection(parent)
+}
+
+/// Split `area` into `cols` x `rows` cells of equal size, row by row from the top left cell.
+///
+/// When the size of `area` isn't divisible, the remaining columns and rows are spread between
+/// the cells, so the cells are at most one column or row apart in size and fill `area` exactly,
+/// without gaps. No cells are returned when `cols` or `rows` is zero.
+///
+/// ```rust
+/// # use ratatui::layout::Rect;
+/// # use ratatecs::layout::grid;
+/// let cells = grid(Rect::new(0, 0, 10, 4), 2, 2);
+/// assert_eq!(
+///     cells,
+///     [
+///         Rect::new(0, 0, 5, 2),
+///         Rect::new(5, 0, 5, 2),
+///         Rect::new(0, 2, 5, 2),
+///         Rect::new(5, 2, 5, 2),
+///     ]
+/// );
+///
+/// // 10 columns in 3 and 5 rows in 2
+/// let cells = grid(Rect::new(1, 1, 10, 5), 3, 2);
+/// let widths: Vec<_> = cells[..3].iter().map(|cell| cell.width).collect();
+/// assert_eq!(widths, [3, 3, 4]);
+/// assert_eq!((cells[0].height, cells[3].height), (2, 3));
+/// assert_eq!(cells[5], Rect::new(7, 3, 4, 3));
+/// assert_eq!(cells.iter().map(|cell| cell.area()).sum::<u32>(), 50);
+///
+/// assert!(grid(Rect::new(0, 0, 10, 4), 0, 2).is_empty());
+/// ```
+pub fn grid(area: Rect, cols: u16, rows: u16) -> Vec<Rect> {
+    // The start of the cell `index` of `count` splitting `length` from `start`
+    let split = |start: u16, length: u16, count: u16, index: u16| {
+        start + (u32::from(length) * u32::from(index) / u32::from(count)) as u16
+    };
+    let mut cells = Vec::with_capacity(usize::from(cols) * usize::from(rows));
+    for row in 0..rows {
+        let y = split(area.y, area.height, rows, row);
+        let bottom = split(area.y, area.height, rows, row + 1);
+        for col in 0..cols {
+            let x = split(area.x, area.width, cols, col);
+            let right = split(area.x, area.width, cols, col + 1);
+            cells.push(Rect::new(x, y, right - x, bottom - y));
+        }
+    }
+    cells
+}
+
+/// A `width` x `height` rect centered in `area`.
+///
+/// The size is clamped to the size of `area`, so the result always fits inside it.
+///
+/// ```rust
+/// # use ratatui::layout::Rect;
+/// # use ratatecs::layout::centered_rect;
+/// let area = Rect::new(0, 0, 80, 24);
+/// assert_eq!(centered_rect(area, 20, 4), Rect::new(30, 10, 20, 4));
+/// assert_eq!(centered_rect(area, 100, 4), Rect::new(0, 10, 80, 4));
+/// ```
+///
+/// A popup wider than the terminal is rendered clamped to it:
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{buffer, snapshot, step};
+/// # use ratatui::{backend::TestBackend, widgets::{Block, Clear}};
+/// fn popup(mut drawer: WidgetDrawer<TestBackend>) {
+///     let area = centered_rect(drawer.area(), 100, 4);
+///     drawer.push_widget(Box::new(Clear), area, 1);
+///     drawer.push_widget(Box::new(Block::bordered()), area, 1);
+/// }
+///
+/// let mut app = App::new_tui_test(10, 10);
+/// app.add_systems(Update, popup);
+/// step(&mut app, 1);
+/// assert_eq!(buffer(&app).area, Rect::new(0, 0, 10, 10));
+/// assert_eq!(
+///     snapshot(&app),
+///     "\n\n\n┌────────┐\n│        │\n│        │\n└────────┘\n\n\n"
+/// );
+/// ```
+pub fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// A rect centered in `area`, taking the given percentages of its width and height.
+///
+/// Percentages above 100 are clamped to 100.
+///
+/// ```rust
+/// # use ratatui::layout::Rect;
+/// # use ratatecs::layout::centered_rect_percent;
+/// let area = Rect::new(0, 0, 80, 24);
+/// assert_eq!(centered_rect_percent(area, 50, 50), Rect::new(20, 6, 40, 12));
+/// assert_eq!(centered_rect_percent(area, 150, 100), area);
+/// ```
+pub fn centered_rect_percent(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let width = u32::from(area.width) * u32::from(percent_x.min(100)) / 100;
+    let height = u32::from(area.height) * u32::from(percent_y.min(100)) / 100;
+    centered_rect(area, width as u16, height as u16)
+}
+
+/// Shrink `area` by `horizontal` columns on the left and right, and `vertical` rows on the top
+/// and bottom.
+///
+/// Margins larger than half of `area` are reduced so the result stays inside it, centered,
+/// instead of underflowing.
+///
+/// ```rust
+/// # use ratatui::layout::Rect;
+/// # use ratatecs::layout::inset;
+/// assert_eq!(inset(Rect::new(0, 0, 80, 24), 10, 10), Rect::new(10, 10, 60, 4));
+/// assert_eq!(inset(Rect::new(0, 0, 10, 10), 10, 10), Rect::new(5, 5, 0, 0));
+/// ```
+pub fn inset(area: Rect, horizontal: u16, vertical: u16) -> Rect {
+    let horizontal = horizontal.min(area.width / 2);
+    let vertical = vertical.min(area.height / 2);
+    Rect {
+        x: area.x + horizontal,
+        y: area.y + vertical,
+        width: area.width - horizontal * 2,
+        height: area.height - vertical * 2,
+    }
+}
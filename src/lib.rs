@@ -1,22 +1,109 @@
 #![allow(clippy::needless_doctest_main)]
 #![doc = include_str!("../README.md")]
 
-use std::{io::Stdout, time::Duration};
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    num::NonZeroU8,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use bevy_app::{
-    App, AppExit, Last, Plugin, PluginGroup, PluginGroupBuilder, PostUpdate, ScheduleRunnerPlugin,
+    App, AppExit, First, Last, MainScheduleOrder, Plugin, PluginGroup, PluginGroupBuilder,
+    PostUpdate, PreUpdate, RunMode, ScheduleRunnerPlugin, Update,
 };
 use bevy_ecs::{
-    event::EventReader,
-    system::{NonSend, NonSendMut, ResMut, Resource, SystemParam},
+    change_detection::DetectChangesMut,
+    event::{Event, EventReader, EventWriter},
+    schedule::{
+        common_conditions::not, InternedScheduleLabel, IntoSystemConfigs, IntoSystemSetConfigs,
+        ScheduleLabel, SystemSet,
+    },
+    system::{Local, NonSend, NonSendMut, Res, ResMut, Resource},
+    world::World,
+};
+use bevy_state::{
+    app::StatesPlugin,
+    state::{StateTransitionEvent, States},
+};
+#[cfg(feature = "termwiz")]
+use ratatui::backend::TermwizBackend;
+use ratatui::{
+    backend::{Backend, TestBackend},
+    buffer::Buffer,
+    layout::{Constraint, Flex, Layout, Rect},
+    prelude::CrosstermBackend,
+    widgets::{Clear, Paragraph, Wrap},
+    Terminal, Viewport,
+};
+
+pub mod accessibility;
+pub mod action;
+pub mod capabilities;
+pub mod color;
+pub mod debug;
+mod draw;
+pub mod export;
+pub mod focus;
+pub mod input;
+mod input_thread;
+pub mod layer;
+pub mod layout;
+pub mod mouse;
+pub mod scroll;
+#[cfg(all(unix, feature = "suspend"))]
+mod suspend;
+pub mod tasks;
+mod terminal;
+#[cfg(feature = "termwiz")]
+mod termwiz;
+pub mod testing;
+pub mod text;
+pub mod text_input;
+pub mod title;
+pub mod viewport;
+pub mod widgets;
+
+pub use draw::{
+    exit_on_render_error, CursorState, FrameArea, FrameStats, LastFrame, NeedsRedraw, RenderError,
+    ScopedWidget, ScreenshotError, WidgetDrawer, WidgetId, WidgetStateId, WidgetsToDraw,
 };
-use bevy_state::app::StatesPlugin;
-use ratatui::{prelude::CrosstermBackend, widgets::WidgetRef, Frame, Terminal};
+pub use terminal::TerminalOutput;
 
 pub mod prelude {
+    pub use crate::accessibility::{reduced_motion_disabled, ReducedMotion};
+    pub use crate::action::{
+        exit_on_quit_requested, Action, InputMap, InputMapPlugin, KeyBinding, QuitPlugin,
+        QuitRequested,
+    };
+    pub use crate::capabilities::TerminalCapabilities;
+    pub use crate::color::ColorProfile;
+    pub use crate::debug::{DebugOverlay, DebugOverlayPlugin};
+    pub use crate::focus::{cycle_focus, focus_nav, has_focus, Focus, Focusable};
+    pub use crate::input::{
+        char_pressed, is_key_press, key_pressed, on_any_key, on_key, on_key_mods, GlobalInput,
+        InputEvent, InputEvents, KeyEvent, KeyPressed, KeyReleased, MouseEvent, PasteEvent,
+        ResizeEvent, TerminalEvent, TerminalFocus,
+    };
+    pub use crate::layer::{LayerId, LayerRegistry};
+    pub use crate::layout::{
+        centered_rect, centered_rect_percent, grid, inset, LayoutRects, Layouts, PanelArea,
+    };
+    pub use crate::mouse::MouseState;
+    pub use crate::scroll::ScrollState;
+    pub use crate::tasks::BackgroundTasks;
+    pub use crate::text_input::{edit_text_input, TextInput};
+    pub use crate::title::TerminalTitle;
+    pub use crate::viewport::{ViewportDrawer, Viewports};
+    pub use crate::widgets::{highlight_focused, titled_block, CompositeWidget};
     pub use crate::{
-        BackendEvent, RatatEcsPlugins, ScopedWidget, TerminalWrapper, TuiPlugin, WidgetDrawer,
-        WidgetsToDraw,
+        below_min_size, exit_on_render_error, exit_with_error, min_size, render_too_small,
+        AfterRestore, AppExt, BackendEvent, BackendEvents, BackendKind, CursorState,
+        DefaultBackend, EventPolling, FrameArea, FrameStats, LastFrame, NeedsRedraw, PendingEvents,
+        PollConfig, RatatEcsPlugins, RecordedFrames, Render, RenderError, RestoreTerminal,
+        ScopedWidget, ScreenshotError, Shutdown, TerminalRestored, TerminalSize, TerminalWrapper,
+        TuiPlugin, WidgetDrawer, WidgetId, WidgetStateId, WidgetsToDraw, WriterBackend,
     };
     pub use bevy_app::prelude::*;
     pub use bevy_app::AppExit;
@@ -26,55 +113,1343 @@ pub mod prelude {
     pub use ratatui::prelude::*;
 }
 
-pub struct TerminalWrapper {
-    pub terminal: Terminal<CrosstermBackend<Stdout>>,
+/// The backend of [`TuiPlugin::new`], crossterm on the standard output.
+///
+/// It's the default backend of [`WidgetDrawer`] and [`TerminalWrapper`], so panels of an app
+/// using it don't have to name a backend. Panels that also render to other backends, like a
+/// [`TestBackend`] in tests, can be generic over the backend instead, and be added with
+/// `panel::<DefaultBackend>`.
+pub type DefaultBackend = CrosstermBackend<TerminalOutput>;
+
+/// The terminal rendered to, as a non-send resource.
+///
+/// The backend defaults to [`DefaultBackend`], as used by [`TuiPlugin::new`], so
+/// `NonSendMut<TerminalWrapper>` is the terminal of most apps.
+pub struct TerminalWrapper<B: Backend = DefaultBackend> {
+    pub terminal: Terminal<B>,
+}
+
+/// The backend used by [`TuiPlugin::with_writer`].
+pub type WriterBackend = CrosstermBackend<Box<dyn Write + Send>>;
+
+/// The backend used to render the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendKind {
+    /// Take over the real terminal through crossterm.
+    Crossterm,
+    /// Take over the real terminal through termwiz.
+    #[cfg(feature = "termwiz")]
+    Termwiz,
+    /// Render to a writer, see [`TuiPlugin::with_writer`].
+    Writer,
+    /// Render to an in-memory [`TestBackend`] of the given size, leaving the real terminal alone.
+    ///
+    /// Only the systems of the test backend are added: the real terminal isn't set up, and
+    /// isn't restored on exit either.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// fn exit(mut exit: EventWriter<AppExit>) {
+    ///     exit.send(AppExit::Success);
+    /// }
+    ///
+    /// let mut app = App::new_tui_test(10, 1);
+    /// app.add_systems(Update, exit);
+    /// assert_eq!(app.run(), AppExit::Success);
+    /// ```
+    Test { width: u16, height: u16 },
+    /// Like [`BackendKind::Test`], also keeping a copy of every rendered frame in
+    /// [`RecordedFrames`].
+    Record { width: u16, height: u16 },
+    /// Render to a terminal built by the app, see [`TuiPlugin::with_terminal`].
+    Custom,
+}
+
+/// Sets up a terminal given to [`TuiPlugin::with_terminal`], with the systems for its backend.
+type InstallTerminal = Box<dyn FnOnce(&mut App, InternedScheduleLabel) + Send>;
+
+/// Sets up the terminal, reads its events and renders the widgets pushed each frame.
+///
+/// It's part of [`RatatEcsPlugins`], and can be configured when adding them:
+///
+/// ```rust,no_run
+/// # use std::time::Duration;
+/// # use ratatecs::prelude::*;
+/// App::new()
+///     .add_plugins(
+///         RatatEcsPlugins::new().set(TuiPlugin::new().poll_timeout(Duration::from_millis(50))),
+///     )
+///     .run();
+/// ```
+pub struct TuiPlugin {
+    backend: BackendKind,
+    writer: Mutex<Option<Box<dyn Write + Send>>>,
+    custom_terminal: Mutex<Option<InstallTerminal>>,
+    poll_timeout: Duration,
+    modes: terminal::Modes,
+    viewport: Viewport,
+    render_when_dirty: bool,
+    threaded_input: bool,
+    buffered_output: bool,
+    exit_on_ctrl_c: bool,
+    render_schedule: InternedScheduleLabel,
+}
+
+impl Default for TuiPlugin {
+    fn default() -> Self {
+        Self {
+            backend: BackendKind::Crossterm,
+            writer: Mutex::new(None),
+            custom_terminal: Mutex::new(None),
+            poll_timeout: PollConfig::default().timeout,
+            modes: terminal::Modes::default(),
+            viewport: Viewport::Fullscreen,
+            render_when_dirty: false,
+            threaded_input: false,
+            buffered_output: false,
+            exit_on_ctrl_c: false,
+            render_schedule: PostUpdate.intern(),
+        }
+    }
 }
 
-pub struct TuiPlugin;
+impl TuiPlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render to the real terminal through termwiz instead of crossterm.
+    #[cfg(feature = "termwiz")]
+    pub fn termwiz() -> Self {
+        Self {
+            backend: BackendKind::Termwiz,
+            ..Self::default()
+        }
+    }
+
+    /// Render to the given writer, like a file or a pipe, instead of the real terminal.
+    ///
+    /// The output is sized like the real terminal if there is one, 80x24 otherwise. There's no
+    /// interactive input in this mode, so no events are polled.
+    pub fn with_writer(writer: Box<dyn Write + Send>) -> Self {
+        Self {
+            backend: BackendKind::Writer,
+            writer: Mutex::new(Some(writer)),
+            ..Self::default()
+        }
+    }
+
+    /// Render to a terminal built by the app, with any backend, instead of setting up the real
+    /// terminal.
+    ///
+    /// The terminal is used as is: nothing is enabled on it, and on exit it's only flushed. Like
+    /// with the test backends, no events are read from it, use [`AppExt::send_test_event`] to
+    /// simulate input. The terminal is available as a [`TerminalWrapper<B>`].
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{snapshot, step};
+    /// # use ratatui::{backend::TestBackend, widgets::Paragraph};
+    /// fn hello(mut drawer: WidgetDrawer<TestBackend>) {
+    ///     drawer.push_widget(Box::new(Paragraph::new("hello")), Rect::new(0, 1, 5, 1), 0);
+    /// }
+    ///
+    /// let terminal = Terminal::new(TestBackend::new(8, 2)).unwrap();
+    /// let mut app = App::new();
+    /// app.add_plugins(RatatEcsPlugins::new().set(TuiPlugin::with_terminal(terminal)));
+    /// app.add_systems(Update, hello);
+    /// step(&mut app, 1);
+    /// assert_eq!(snapshot(&app), "\nhello");
+    /// ```
+    pub fn with_terminal<B: Backend + Send + 'static>(terminal: Terminal<B>) -> Self {
+        let install: InstallTerminal = Box::new(move |app: &mut App, render_schedule| {
+            app.add_systems(
+                Last,
+                (
+                    handle_resize::<B>.after(ReadBackendEvents),
+                    flush_on_exit::<B>.in_set(RestoreTerminal),
+                ),
+            );
+            add_render::<B>(app, render_schedule);
+            insert_terminal(app, terminal);
+        });
+        Self {
+            backend: BackendKind::Custom,
+            custom_terminal: Mutex::new(Some(install)),
+            ..Self::default()
+        }
+    }
+
+    /// Render to a [`TestBackend`] of the given size, recording every frame in [`RecordedFrames`].
+    pub fn record(width: u16, height: u16) -> Self {
+        Self {
+            backend: BackendKind::Record { width, height },
+            ..Self::default()
+        }
+    }
+
+    /// Render to a [`TestBackend`] of the given size instead of the real terminal.
+    pub fn test_backend(width: u16, height: u16) -> Self {
+        Self {
+            backend: BackendKind::Test { width, height },
+            ..Self::default()
+        }
+    }
+
+    /// How long to wait for a terminal event each frame, see [`PollConfig`].
+    pub fn poll_timeout(mut self, timeout: Duration) -> Self {
+        self.poll_timeout = timeout;
+        self
+    }
+
+    /// Put the terminal in raw mode, so that keys are received as they're pressed and aren't
+    /// echoed. Enabled by default.
+    ///
+    /// Disabling it, along with [`TuiPlugin::alternate_screen`], lets `println!` debugging
+    /// interleave with the rendered frames, at the cost of garbled rendering. Keys are then only
+    /// received once Enter is pressed, and Ctrl+C stops the app without restoring the terminal.
+    ///
+    /// Only used by the crossterm backend.
+    pub fn raw_mode(mut self, enabled: bool) -> Self {
+        self.modes.raw_mode = enabled;
+        self
+    }
+
+    /// Render on the alternate screen, leaving the scrollback as it was on exit. Enabled by
+    /// default when rendering to the whole screen, see [`TuiPlugin::viewport`] which sets it
+    /// again.
+    ///
+    /// Disabled, frames are rendered over the normal screen, still in raw mode unless disabled
+    /// with [`TuiPlugin::raw_mode`], like interactive filters such as fzf. On exit, the last
+    /// frame is left in the scrollback, with the cursor on the line below it, and only the
+    /// enabled modes are disabled.
+    ///
+    /// Only used by the crossterm backend.
+    pub fn alternate_screen(mut self, enabled: bool) -> Self {
+        self.modes.alternate_screen = enabled;
+        self
+    }
+
+    /// Capture the mouse so that [`MouseEvent`](input::MouseEvent)s are received. Disabled by
+    /// default, as capturing the mouse prevents selecting text in the terminal.
+    ///
+    /// Only used by the crossterm backend. Use [`WidgetsToDraw::hit_test`] to find the widget
+    /// under the mouse.
+    pub fn enable_mouse(mut self, enabled: bool) -> Self {
+        self.modes.mouse_capture = enabled;
+        self
+    }
+
+    /// Enable bracketed paste, so that pasted text is received as a single
+    /// [`PasteEvent`](input::PasteEvent) instead of one key event per character. Disabled by
+    /// default.
+    ///
+    /// Only used by the crossterm backend. Bracketed paste is disabled again on exit, and if the
+    /// app panics.
+    pub fn enable_paste(mut self, enabled: bool) -> Self {
+        self.modes.bracketed_paste = enabled;
+        self
+    }
+
+    /// Report when the terminal gains or loses the focus, in [`TerminalFocus`](input::TerminalFocus).
+    /// Disabled by default.
+    ///
+    /// Only used by the crossterm backend, and only some terminals report it. Focus reporting is
+    /// disabled again on exit, and if the app panics.
+    pub fn enable_focus_change(mut self, enabled: bool) -> Self {
+        self.modes.focus_change = enabled;
+        self
+    }
+
+    /// Push the keyboard enhancement `flags` of the kitty keyboard protocol, to receive key
+    /// releases and repeats, and tell apart keys the terminal otherwise sends the same way, like
+    /// Ctrl+I and Tab. None by default.
+    ///
+    /// Only used by the crossterm backend, and only if the terminal supports the protocol, like
+    /// kitty, WezTerm, foot or Alacritty. Otherwise the flags are ignored and key events are
+    /// received as usual. Terminals that don't answer the support query delay the startup by up
+    /// to 2 seconds. The flags are popped again on exit, and if the app panics. The extended
+    /// key events are received unchanged in [`BackendEvents`] and as
+    /// [`KeyEvent`](input::KeyEvent)s, use [`is_key_press`](input::is_key_press) to not act on
+    /// releases. Whether the flags were pushed is in
+    /// [`TerminalCapabilities::kitty_keyboard`](capabilities::TerminalCapabilities::kitty_keyboard).
+    pub fn keyboard_enhancement(
+        mut self,
+        flags: crossterm::event::KeyboardEnhancementFlags,
+    ) -> Self {
+        self.modes.keyboard_enhancement = flags;
+        self
+    }
+
+    /// Only render frames when something changed, instead of every frame, to use less CPU when
+    /// idle. Disabled by default.
+    ///
+    /// A frame is rendered after events are received from the terminal, or when requested with
+    /// [`WidgetDrawer::request_redraw`], see [`NeedsRedraw`]. State transitions don't come with
+    /// events, use [`AppExt::redraw_on_state_change`] to render them.
+    pub fn render_when_dirty(mut self, enabled: bool) -> Self {
+        self.render_when_dirty = enabled;
+        self
+    }
+
+    /// Read the terminal events on a background thread, so that the frame doesn't wait on the
+    /// terminal. Disabled by default.
+    ///
+    /// Each frame still waits for the first event up to the [`PollConfig`] timeout, but wakes up
+    /// as soon as it arrives. Use a zero timeout to never wait. The thread stops on [`AppExit`].
+    ///
+    /// Only used by the crossterm backend, the test backends never read the terminal.
+    pub fn threaded_input(mut self, enabled: bool) -> Self {
+        self.threaded_input = enabled;
+        self
+    }
+
+    /// Buffer what is written to the standard output, and write it to the terminal once per
+    /// frame when the frame is flushed, instead of every time the standard output's own buffer
+    /// fills up. Disabled by default.
+    ///
+    /// This writes large frames in fewer writes, for apps redrawing at a high frame rate. The
+    /// buffer is flushed before the terminal is restored on exit, but a panic while rendering
+    /// drops the part of the frame already written.
+    ///
+    /// Only used by the crossterm backend.
+    pub fn buffered_output(mut self, enabled: bool) -> Self {
+        self.buffered_output = enabled;
+        self
+    }
+
+    /// Exit the app with [`AppExit::Success`] when Ctrl+C is pressed. Disabled by default, so
+    /// apps can use Ctrl+C for something else, like copying.
+    ///
+    /// In raw mode, Ctrl+C is received as a key press rather than stopping the app.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::step;
+    /// use event::{KeyCode, KeyEvent, KeyModifiers};
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins(
+    ///     RatatEcsPlugins::new().set(TuiPlugin::test_backend(10, 1).exit_on_ctrl_c(true)),
+    /// );
+    /// let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+    /// app.send_test_event(event::Event::Key(ctrl_c));
+    /// step(&mut app, 2);
+    /// assert!(!app.world().resource::<Events<AppExit>>().is_empty());
+    /// ```
+    pub fn exit_on_ctrl_c(mut self, enabled: bool) -> Self {
+        self.exit_on_ctrl_c = enabled;
+        self
+    }
+
+    /// Render in `schedule` instead of [`PostUpdate`], like [`Last`] to render widgets pushed by
+    /// systems in [`Last`] too.
+    ///
+    /// Rendering is done by the [`Render`] set, so systems pushing widgets must run before it:
+    /// systems in a schedule running before `schedule` always do, and systems in `schedule`
+    /// must be ordered with `.before(Render)`. In [`Last`], [`Render`] runs after the events of
+    /// the frame are read, and before [`Shutdown`].
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{snapshot, step};
+    /// # use ratatui::widgets::Paragraph;
+    /// fn late(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     widgets.push_widget(Box::new(Paragraph::new("late")), Rect::new(0, 0, 4, 1), 0);
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins(
+    ///     RatatEcsPlugins::new().set(TuiPlugin::test_backend(4, 1).render_schedule(Last)),
+    /// );
+    /// app.add_systems(Last, late.before(Render));
+    /// step(&mut app, 1);
+    /// assert_eq!(snapshot(&app), "late");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// When the plugin is built, if `schedule` isn't run by the main schedule after [`Update`],
+    /// as rendering wouldn't see the widgets pushed during [`Update`].
+    pub fn render_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.render_schedule = schedule.intern();
+        self
+    }
+
+    /// Render in `height` lines below the cursor instead of taking over the whole screen, leaving
+    /// the scrollback intact. The lines are cleared on exit.
+    ///
+    /// Only used by the crossterm backend.
+    pub fn inline(height: u16) -> Self {
+        Self::default().viewport(Viewport::Inline(height))
+    }
+
+    /// The part of the terminal to render to, the whole screen by default.
+    ///
+    /// Only the fullscreen viewport uses the alternate screen, unless changed afterwards with
+    /// [`TuiPlugin::alternate_screen`]. The other viewports are cleared on exit, and a
+    /// [`Viewport::Fixed`] area isn't resized with the terminal. The area of the viewport is
+    /// available in [`FrameArea`], while [`TerminalSize`] stays the size of the whole terminal.
+    ///
+    /// Only used by the crossterm backend.
+    pub fn viewport(mut self, viewport: Viewport) -> Self {
+        self.modes.alternate_screen = viewport == Viewport::Fullscreen;
+        self.viewport = viewport;
+        self
+    }
+}
 
 impl Plugin for TuiPlugin {
     fn build(&self, app: &mut App) {
+        if let Some(order) = app.world().get_resource::<MainScheduleOrder>() {
+            let position = |label| order.labels.iter().position(|&l| l == label);
+            assert!(
+                matches!(
+                    (position(Update.intern()), position(self.render_schedule)),
+                    (Some(update), Some(render)) if render > update
+                ),
+                "the render schedule {:?} must run after `Update` in the main schedule",
+                self.render_schedule
+            );
+        }
+        app.insert_resource(PollConfig {
+            timeout: self.poll_timeout,
+        });
         app.insert_resource(BackendEvent(None));
+        app.insert_resource(BackendEvents::default());
+        app.init_resource::<EventPolling>();
+        app.configure_sets(Last, ReadBackendEvents.run_if(backend_events_read));
+        app.add_systems(
+            Last,
+            skip_backend_events
+                .before(ReadBackendEvents)
+                .run_if(not(backend_events_read)),
+        );
+        app.insert_resource(TerminalSize::default());
+        app.insert_resource(CursorState::default());
+        app.init_resource::<focus::Focus>();
+        app.init_resource::<viewport::Viewports>();
+        app.init_resource::<FrameStats>();
+        app.init_resource::<layout::Layouts>();
+        app.init_resource::<layout::LayoutRects>();
+        app.init_resource::<layer::LayerRegistry>();
+        app.init_resource::<mouse::MouseState>();
+        app.init_resource::<input::TerminalFocus>();
+        app.init_resource::<input::InputEvents>();
+        app.init_resource::<title::TerminalTitle>();
+        app.init_resource::<tasks::BackgroundTasks>();
+        app.insert_resource(match self.backend {
+            BackendKind::Test { .. } | BackendKind::Record { .. } | BackendKind::Custom => {
+                color::ColorProfile::Truecolor
+            }
+            _ => color::ColorProfile::detect(),
+        });
+        app.insert_resource(match self.backend {
+            BackendKind::Test { .. } | BackendKind::Record { .. } | BackendKind::Custom => {
+                capabilities::TerminalCapabilities::ALL
+            }
+            _ => capabilities::TerminalCapabilities::detect(),
+        });
+        app.insert_resource(match self.backend {
+            BackendKind::Test { .. } | BackendKind::Record { .. } | BackendKind::Custom => {
+                accessibility::ReducedMotion::default()
+            }
+            _ => accessibility::ReducedMotion::from_env(),
+        });
+        app.add_systems(
+            PreUpdate,
+            (
+                layout::resolve_layouts,
+                focus::register_focusables,
+                tasks::poll_tasks,
+            ),
+        );
+        app.add_event::<input::TerminalEvent>();
+        app.add_event::<input::KeyEvent>();
+        app.add_event::<input::KeyPressed>();
+        app.add_event::<input::KeyReleased>();
+        app.add_event::<input::MouseEvent>();
+        app.add_event::<input::ResizeEvent>();
+        app.add_event::<input::PasteEvent>();
+
+        app.add_systems(
+            Last,
+            (
+                input::send_terminal_events,
+                input::send_input_events,
+                input::update_terminal_focus,
+                mouse::update_mouse_state,
+                scroll::scroll_on_wheel,
+            )
+                .after(ReadBackendEvents),
+        );
+        // Updated at the start of the next frame rather than right after reading the events, so
+        // that it holds each event for exactly one frame, whatever the order of the systems in
+        // `Last`
+        app.add_systems(First, (update_backend_event, input::update_input_events));
+        app.configure_sets(
+            Last,
+            (
+                Shutdown.run_if(first_exit),
+                RestoreTerminal.after(Shutdown).run_if(first_exit),
+                AfterRestore.after(RestoreTerminal).run_if(first_exit),
+                Render.after(ReadBackendEvents).before(Shutdown),
+            ),
+        );
+        app.add_event::<TerminalRestored>();
+        app.add_event::<RenderError>();
+        app.add_event::<ScreenshotError>();
+        app.add_systems(Last, send_terminal_restored.in_set(AfterRestore));
+        app.insert_non_send_resource(WidgetsToDraw::default());
+        if self.exit_on_ctrl_c {
+            app.add_systems(
+                PreUpdate,
+                exit_on_ctrl_c.run_if(input::on_key_mods(
+                    crossterm::event::KeyCode::Char('c'),
+                    crossterm::event::KeyModifiers::CONTROL,
+                )),
+            );
+        }
+        if self.render_when_dirty {
+            app.init_resource::<NeedsRedraw>();
+            app.add_systems(
+                Last,
+                redraw_on_events.after(ReadBackendEvents).before(Render),
+            );
+        }
+
+        match self.backend {
+            BackendKind::Crossterm => {
+                app.init_resource::<ExitErrors>();
+                app.add_systems(Last, (cleanup_on_exit.in_set(RestoreTerminal),));
+                app.add_systems(PostUpdate, title::apply_title::<TerminalOutput>);
+                app.add_systems(
+                    Last,
+                    title::restore_title_on_exit::<TerminalOutput>
+                        .in_set(RestoreTerminal)
+                        .before(cleanup_on_exit),
+                );
+                // The size of a fixed viewport doesn't depend on the terminal
+                if !matches!(self.viewport, Viewport::Fixed(_)) {
+                    app.add_systems(
+                        Last,
+                        handle_resize::<DefaultBackend>.after(ReadBackendEvents),
+                    );
+                }
+                add_render::<DefaultBackend>(app, self.render_schedule);
+                if self.viewport != Viewport::Fullscreen {
+                    app.add_systems(
+                        Last,
+                        clear_viewport_on_exit
+                            .in_set(RestoreTerminal)
+                            .before(cleanup_on_exit),
+                    );
+                } else if !self.modes.alternate_screen {
+                    app.add_systems(
+                        Last,
+                        move_below_frame_on_exit
+                            .in_set(RestoreTerminal)
+                            .before(cleanup_on_exit),
+                    );
+                }
+
+                let terminal =
+                    terminal::init(self.modes, self.viewport.clone(), self.buffered_output);
+                insert_terminal(app, terminal);
+                // The terminal was only queried if the flags were requested
+                if !self.modes.keyboard_enhancement.is_empty() {
+                    app.world_mut()
+                        .resource_mut::<capabilities::TerminalCapabilities>()
+                        .kitty_keyboard = terminal::enabled_modes()
+                        .is_some_and(|modes| !modes.keyboard_enhancement.is_empty());
+                }
+
+                #[cfg(all(unix, feature = "suspend"))]
+                {
+                    let (signal, registered) = suspend::SuspendSignal::register();
+                    app.insert_resource(signal);
+                    if let Err(err) = registered {
+                        app.world_mut().send_event(RenderError(err));
+                    }
+                    app.add_systems(Last, suspend::suspend_on_ctrl_z.after(ReadBackendEvents));
+                }
 
-        app.add_systems(Last, (get_backend_events, cleanup_on_exit));
-        app.add_systems(PostUpdate, render);
+                if self.threaded_input {
+                    app.insert_resource(input_thread::InputThread::spawn());
+                    app.add_systems(
+                        Last,
+                        (
+                            input_thread::get_threaded_events.in_set(ReadBackendEvents),
+                            input_thread::stop_on_exit
+                                .in_set(RestoreTerminal)
+                                .before(cleanup_on_exit),
+                        ),
+                    );
+                } else {
+                    app.add_systems(Last, get_backend_events.in_set(ReadBackendEvents));
+                }
+            }
+            #[cfg(feature = "termwiz")]
+            BackendKind::Termwiz => {
+                app.add_systems(
+                    Last,
+                    (
+                        termwiz::get_backend_events.in_set(ReadBackendEvents),
+                        handle_resize::<TermwizBackend>.after(ReadBackendEvents),
+                        termwiz::cleanup_on_exit.in_set(RestoreTerminal),
+                    ),
+                );
+                add_render::<TermwizBackend>(app, self.render_schedule);
+
+                let terminal = termwiz::init();
+                insert_terminal(app, terminal);
+            }
+            BackendKind::Writer => {
+                app.add_systems(Last, flush_on_exit::<WriterBackend>.in_set(RestoreTerminal));
+                app.add_systems(PostUpdate, title::apply_title::<Box<dyn Write + Send>>);
+                app.add_systems(
+                    Last,
+                    title::restore_title_on_exit::<Box<dyn Write + Send>>
+                        .in_set(RestoreTerminal)
+                        .before(flush_on_exit::<WriterBackend>),
+                );
+                add_render::<WriterBackend>(app, self.render_schedule);
+
+                let writer = self
+                    .writer
+                    .lock()
+                    .ok()
+                    .and_then(|mut writer| writer.take())
+                    .expect("the writer was already used by another app");
+                let terminal = terminal::init_writer(writer);
+                insert_terminal(app, terminal);
+            }
+            BackendKind::Test { width, height } | BackendKind::Record { width, height } => {
+                if matches!(self.backend, BackendKind::Record { .. }) {
+                    app.init_resource::<RecordedFrames>();
+                    app.add_systems(self.render_schedule, record_frame.after(Render));
+                }
+                app.init_resource::<PendingEvents>();
+                app.add_systems(
+                    Last,
+                    (
+                        get_test_events.in_set(ReadBackendEvents),
+                        (resize_test_backend, handle_resize::<TestBackend>)
+                            .chain()
+                            .after(ReadBackendEvents),
+                    ),
+                );
+                add_render::<TestBackend>(app, self.render_schedule);
+
+                let terminal = Terminal::new(TestBackend::new(width, height))
+                    .expect("failed to initialize test terminal");
+                insert_terminal(app, terminal);
+            }
+            BackendKind::Custom => {
+                app.init_resource::<PendingEvents>();
+                app.add_systems(Last, get_test_events.in_set(ReadBackendEvents));
+
+                let install = self
+                    .custom_terminal
+                    .lock()
+                    .ok()
+                    .and_then(|mut install| install.take())
+                    .expect("the terminal was already used by another app");
+                install(app, self.render_schedule);
+            }
+        }
+    }
+}
 
-        let terminal = ratatui::init();
-        app.insert_non_send_resource(TerminalWrapper { terminal });
-        app.insert_non_send_resource(WidgetsToDraw { widgets: vec![] });
+/// Insert the terminal, along with its size so it's known before the first frame is rendered.
+fn insert_terminal<B: Backend + 'static>(app: &mut App, mut terminal: Terminal<B>) {
+    if let Ok(size) = terminal.size() {
+        app.insert_resource(TerminalSize(size.width, size.height));
     }
+    app.insert_resource(FrameArea(terminal.get_frame().area()));
+    app.insert_non_send_resource(TerminalWrapper { terminal });
 }
 
-pub struct RatatEcsPlugins;
+/// The plugins needed to run a TUI: [`TuiPlugin`], [`StatesPlugin`], and a
+/// [`ScheduleRunnerPlugin`] running frames in a loop.
+#[derive(Debug, Clone, Copy)]
+pub struct RatatEcsPlugins {
+    frame_rate: u32,
+    states: bool,
+    run_mode: Option<RunMode>,
+    run_for: Option<Duration>,
+}
+
+impl Default for RatatEcsPlugins {
+    fn default() -> Self {
+        Self {
+            frame_rate: 0,
+            states: true,
+            run_mode: None,
+            run_for: None,
+        }
+    }
+}
+
+impl RatatEcsPlugins {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run at most `fps` frames per second, waiting between frames. `0`, the default, runs frames
+    /// back to back.
+    ///
+    /// Uncapped, input is handled as soon as possible, but a core is kept busy while the
+    /// terminal is idle, apart from the time spent waiting for events (see [`PollConfig`]). A cap
+    /// saves CPU and battery, at the cost of up to a frame of latency.
+    ///
+    /// ```rust
+    /// # use std::time::Duration;
+    /// # use ratatecs::RatatEcsPlugins;
+    /// assert_eq!(RatatEcsPlugins::new().frame_rate(50).wait(), Duration::from_millis(20));
+    /// assert_eq!(RatatEcsPlugins::new().frame_rate(0).wait(), Duration::ZERO);
+    /// ```
+    pub fn frame_rate(mut self, fps: u32) -> Self {
+        self.frame_rate = fps;
+        self
+    }
+
+    /// Leave out the [`StatesPlugin`], when it's already added by other plugins.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// use bevy_state::app::StatesPlugin;
+    ///
+    /// App::new()
+    ///     .add_plugins(StatesPlugin)
+    ///     .add_plugins(RatatEcsPlugins::new().without_states().set(TuiPlugin::test_backend(10, 1)));
+    /// ```
+    pub fn without_states(mut self) -> Self {
+        self.states = false;
+        self
+    }
+
+    /// Run frames with this runner, instead of in a loop at the [`RatatEcsPlugins::frame_rate`].
+    pub fn with_runner(mut self, runner: ScheduleRunnerPlugin) -> Self {
+        self.run_mode = Some(runner.run_mode);
+        self
+    }
+
+    /// Exit with [`AppExit::Success`] once `duration` has passed since the first frame, like for
+    /// recording demos or smoke tests that must end. The terminal is restored as on any other
+    /// exit.
+    ///
+    /// ```rust
+    /// # use std::time::{Duration, Instant};
+    /// # use ratatecs::prelude::*;
+    /// let started = Instant::now();
+    /// let exit = App::new()
+    ///     .add_plugins(
+    ///         RatatEcsPlugins::new()
+    ///             .run_for(Duration::from_millis(50))
+    ///             .set(TuiPlugin::test_backend(10, 1)),
+    ///     )
+    ///     .run();
+    /// assert_eq!(exit, AppExit::Success);
+    /// assert!(started.elapsed() >= Duration::from_millis(50));
+    /// ```
+    pub fn run_for(mut self, duration: Duration) -> Self {
+        self.run_for = Some(duration);
+        self
+    }
+
+    /// The minimum duration of a frame, given to the [`ScheduleRunnerPlugin`].
+    pub fn wait(&self) -> Duration {
+        match self.frame_rate {
+            0 => Duration::ZERO,
+            fps => Duration::from_secs(1) / fps,
+        }
+    }
+}
 
 impl PluginGroup for RatatEcsPlugins {
     fn build(self) -> bevy_app::PluginGroupBuilder {
         let mut builder = PluginGroupBuilder::start::<Self>();
-        builder = builder.add(TuiPlugin);
-        builder = builder.add(StatesPlugin);
+        builder = builder.add(TuiPlugin::default());
+        if self.states {
+            builder = builder.add(StatesPlugin);
+        }
         builder = builder.add(ScheduleRunnerPlugin {
-            run_mode: bevy_app::RunMode::Loop {
-                wait: Some(Duration::from_millis(0)),
-            },
+            run_mode: self.run_mode.unwrap_or(RunMode::Loop {
+                wait: Some(self.wait()),
+            }),
         });
+        if let Some(duration) = self.run_for {
+            builder = builder.add(RunFor(duration));
+        }
 
         builder
     }
 }
 
+/// Exits the app after a duration, see [`RatatEcsPlugins::run_for`].
+struct RunFor(Duration);
+
+impl Plugin for RunFor {
+    fn build(&self, app: &mut App) {
+        let duration = self.0;
+        app.add_systems(
+            First,
+            move |mut deadline: Local<Option<Instant>>, mut exit: EventWriter<AppExit>| {
+                let now = Instant::now();
+                if now >= *deadline.get_or_insert(now + duration) {
+                    exit.send(AppExit::Success);
+                }
+            },
+        );
+    }
+}
+
+/// Shortcuts to create an [`App`] set up with [`RatatEcsPlugins`].
+pub trait AppExt {
+    /// Create an [`App`] rendering to the real terminal.
+    fn new_tui() -> App;
+
+    /// Create an [`App`] rendering to a [`TestBackend`] of the given size.
+    fn new_tui_test(width: u16, height: u16) -> App;
+
+    /// Queue an event to be received next frame, as if it came from the terminal.
+    ///
+    /// Only available with the test backends and [`TuiPlugin::with_terminal`], see
+    /// [`PendingEvents`].
+    fn send_test_event(&mut self, event: crossterm::event::Event) -> &mut Self;
+
+    /// Register a panel that can take the keyboard focus, see [`focus`].
+    fn add_focusable(&mut self, id: &'static str) -> &mut Self;
+
+    /// Add a panel: a function adding the systems and resources of a part of the app.
+    ///
+    /// Unlike with [`App::add_plugins`], the same panel can be added several times, and panels
+    /// can be closures capturing their configuration.
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{assert_buffer_contains, step};
+    /// # use ratatui::widgets::Paragraph;
+    /// mod greeting {
+    ///     use ratatecs::prelude::*;
+    ///     use ratatui::widgets::Paragraph;
+    ///
+    ///     pub fn panel(app: &mut App) {
+    ///         app.add_systems(PostUpdate, render);
+    ///     }
+    ///
+    ///     // Renders to the terminal of `TuiPlugin::new`, without naming its backend
+    ///     fn render(mut drawer: WidgetDrawer) {
+    ///         drawer.push_widget(Box::new(Paragraph::new("hello")), drawer.area(), 0);
+    ///     }
+    /// }
+    ///
+    /// fn label(text: &'static str, x: u16) -> impl FnOnce(&mut App) {
+    ///     move |app| {
+    ///         app.add_systems(Update, move |mut widgets: NonSendMut<WidgetsToDraw>| {
+    ///             widgets.push_widget(Box::new(Paragraph::new(text)), Rect::new(x, 0, 3, 1), 0);
+    ///         });
+    ///     }
+    /// }
+    ///
+    /// # #[allow(unused)]
+    /// # fn main_app() {
+    /// App::new()
+    ///     .add_plugins(RatatEcsPlugins::new())
+    ///     .add_panel(greeting::panel);
+    /// # }
+    ///
+    /// let mut app = App::new_tui_test(7, 1);
+    /// app.add_panel(label("one", 0)).add_panel(label("two", 4));
+    /// step(&mut app, 1);
+    /// assert_buffer_contains(&app, "one two");
+    /// ```
+    fn add_panel(&mut self, panel: impl FnOnce(&mut App)) -> &mut Self;
+
+    /// Register a layer above the previously registered ones, see [`layer`].
+    fn add_layer(&mut self, name: &'static str) -> &mut Self;
+
+    /// Split the terminal with `layout`, resolving the areas in
+    /// [`LayoutRects`](layout::LayoutRects) under `names`.
+    fn add_layout(
+        &mut self,
+        layout: Layout,
+        names: impl IntoIterator<Item = &'static str>,
+    ) -> &mut Self;
+
+    /// Split the area named `parent` with `layout`, see [`layout::Layouts::add_in`].
+    fn add_layout_in(
+        &mut self,
+        parent: &'static str,
+        layout: Layout,
+        names: impl IntoIterator<Item = &'static str>,
+    ) -> &mut Self;
+
+    /// Render a frame after every transition of the state `S`, when rendering only when needed
+    /// with [`TuiPlugin::render_when_dirty`].
+    ///
+    /// State transitions are applied the frame after [`NextState`] is set, without terminal
+    /// events to trigger a redraw, so panels shown or hidden by the state would otherwise only
+    /// change on screen after the next event. Systems in [`OnEnter`] and [`OnExit`] run in the
+    /// transition, between [`PreUpdate`] and [`Update`], so widgets they push, like the first
+    /// frame of an animation, are rendered that same frame.
+    ///
+    /// [`NextState`]: bevy_state::state::NextState
+    /// [`OnEnter`]: bevy_state::state::OnEnter
+    /// [`OnExit`]: bevy_state::state::OnExit
+    /// [`PreUpdate`]: bevy_app::PreUpdate
+    /// [`Update`]: bevy_app::Update
+    ///
+    /// ```rust
+    /// # use ratatecs::prelude::*;
+    /// # use ratatecs::testing::{snapshot, step};
+    /// # use ratatui::widgets::Paragraph;
+    /// #[derive(States, Default, Debug, Hash, PartialEq, Eq, Clone)]
+    /// enum Popup {
+    ///     Open,
+    ///     #[default]
+    ///     Closed,
+    /// }
+    ///
+    /// fn open(mut popup: ResMut<NextState<Popup>>) {
+    ///     popup.set(Popup::Open);
+    /// }
+    ///
+    /// fn popup(mut widgets: NonSendMut<WidgetsToDraw>) {
+    ///     widgets.push_widget(Box::new(Paragraph::new("popup")), Rect::new(0, 0, 5, 1), 0);
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins(
+    ///     RatatEcsPlugins::new().set(TuiPlugin::test_backend(5, 1).render_when_dirty(true)),
+    /// );
+    /// app.init_state::<Popup>();
+    /// app.redraw_on_state_change::<Popup>();
+    /// app.add_systems(
+    ///     Update,
+    ///     (
+    ///         open.run_if(on_key(event::KeyCode::Char('p'))),
+    ///         popup.run_if(in_state(Popup::Open)),
+    ///     ),
+    /// );
+    /// step(&mut app, 1);
+    ///
+    /// app.send_test_event(event::Event::Key(event::KeyCode::Char('p').into()));
+    /// step(&mut app, 3);
+    /// assert_eq!(snapshot(&app), "popup");
+    /// ```
+    fn redraw_on_state_change<S: States>(&mut self) -> &mut Self;
+}
+
+impl AppExt for App {
+    fn new_tui() -> App {
+        let mut app = App::new();
+        app.add_plugins(RatatEcsPlugins::new());
+        app
+    }
+
+    fn new_tui_test(width: u16, height: u16) -> App {
+        let mut app = App::new();
+        app.add_plugins(RatatEcsPlugins::new().set(TuiPlugin::test_backend(width, height)));
+        app
+    }
+
+    fn send_test_event(&mut self, event: crossterm::event::Event) -> &mut Self {
+        self.world_mut()
+            .get_resource_mut::<PendingEvents>()
+            .expect("test events can only be sent with a test backend")
+            .0
+            .push_back(event);
+        self
+    }
+
+    fn add_focusable(&mut self, id: &'static str) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<focus::Focus>()
+            .register(id);
+        self
+    }
+
+    fn add_panel(&mut self, panel: impl FnOnce(&mut App)) -> &mut Self {
+        panel(self);
+        self
+    }
+
+    fn redraw_on_state_change<S: States>(&mut self) -> &mut Self {
+        self.add_systems(Update, redraw_on_state_change::<S>)
+    }
+
+    fn add_layer(&mut self, name: &'static str) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<layer::LayerRegistry>()
+            .register(name);
+        self
+    }
+
+    fn add_layout(
+        &mut self,
+        layout: Layout,
+        names: impl IntoIterator<Item = &'static str>,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<layout::Layouts>()
+            .add(layout, names);
+        self
+    }
+
+    fn add_layout_in(
+        &mut self,
+        parent: &'static str,
+        layout: Layout,
+        names: impl IntoIterator<Item = &'static str>,
+    ) -> &mut Self {
+        self.world_mut()
+            .get_resource_or_init::<layout::Layouts>()
+            .add_in(parent, layout, names);
+        self
+    }
+}
+
+/// Give the terminal back to the user while `run` runs, like to open an editor or a pager, then
+/// take it over again and repaint it fully on the next frame.
+///
+/// The terminal modes are restored as they were before the app started, and enabled again
+/// afterwards. With [`TuiPlugin::threaded_input`], the input thread is stopped so that it doesn't
+/// take the keys meant for the child program. With the test backends, `run` just runs.
+///
+/// The terminal is a non-send resource, so this needs the whole [`World`]: call it from an
+/// exclusive system, which runs on the main thread.
+///
+/// ```rust,no_run
+/// use std::process::Command;
+///
+/// use ratatecs::prelude::*;
+///
+/// fn open_editor(world: &mut World) {
+///     match ratatecs::suspend_and_run(world, || Command::new("vi").arg("notes.txt").status()) {
+///         Ok(Ok(status)) if status.success() => (),
+///         Ok(Ok(status)) => eprintln!("editor failed: {status}"),
+///         Ok(Err(err)) | Err(err) => eprintln!("failed to run the editor: {err}"),
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(RatatEcsPlugins::new())
+///     .add_systems(Update, open_editor.run_if(on_key(event::KeyCode::Char('e'))))
+///     .run();
+/// ```
+///
+/// # Errors
+///
+/// If the terminal couldn't be restored, then `run` isn't run, or set up again, then it's left
+/// restored and `run` has run. Either way, the events are read again and the next frame is fully
+/// redrawn, so the app keeps getting input.
+pub fn suspend_and_run<R>(world: &mut World, run: impl FnOnce() -> R) -> io::Result<R> {
+    let input_thread = world.remove_resource::<input_thread::InputThread>();
+    if let Some(input_thread) = &input_thread {
+        input_thread.stop();
+    }
+
+    let result = terminal::leave().and_then(|modes| {
+        let result = run();
+        if let Some(modes) = modes {
+            terminal::enter(modes)?;
+        }
+        Ok(result)
+    });
+
+    // Also when failing, not to leave the app without input
+    if input_thread.is_some() {
+        world.insert_resource(input_thread::InputThread::spawn());
+    }
+    if let Some(mut widgets) = world.get_non_send_resource_mut::<WidgetsToDraw>() {
+        widgets.force_clear = true;
+    }
+    if let Some(mut redraw) = world.get_resource_mut::<NeedsRedraw>() {
+        redraw.set_if_neq(NeedsRedraw(true));
+    }
+    result
+}
+
+/// The most recent event received from the backend during the last frame, if any.
+///
+/// When several events arrive in the same frame, only the last one is kept here. Use
+/// [`BackendEvents`] to see all of them, or read [`TerminalEvent`](input::TerminalEvent)s to see
+/// each of them exactly once.
+///
+/// It's updated in `First`, and holds the event until the `First` of the following frame.
 #[derive(Resource)]
 pub struct BackendEvent(pub Option<crossterm::event::Event>);
 
-fn get_backend_events(mut event: ResMut<BackendEvent>) {
-    let new_event = crossterm::event::poll(Duration::from_millis(10))
-        .ok()
-        .and_then(|has_event| {
-            if has_event {
-                crossterm::event::read().ok()
-            } else {
-                None
-            }
-        });
+/// All events received from the backend during the last frame, in the order they arrived.
+#[derive(Resource, Default)]
+pub struct BackendEvents(pub Vec<crossterm::event::Event>);
+
+/// How long to wait for a terminal event each frame before moving on.
+///
+/// A longer timeout uses less CPU, a shorter one reduces input latency. Defaults to 10ms.
+///
+/// The frame an [`AppExit`] is sent doesn't wait, so the app exits right away. Events still
+/// pending then are left unread, only [`AppExit`]s sent in [`Last`] after the events are read
+/// wait for the timeout:
+///
+/// ```rust
+/// # use std::time::{Duration, Instant};
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// let mut app = App::new_tui_test(10, 1);
+/// app.insert_resource(PollConfig { timeout: Duration::from_secs(5) });
+/// // Paused polling waits for the timeout, like the crossterm backend without events
+/// app.world_mut().resource_mut::<EventPolling>().pause();
+/// app.send_test_event(event::Event::FocusLost);
+/// app.world_mut().send_event(AppExit::Success);
+///
+/// let started = Instant::now();
+/// step(&mut app, 1);
+/// assert!(started.elapsed() < Duration::from_secs(5));
+///
+/// app.world_mut().resource_mut::<EventPolling>().resume();
+/// app.world_mut().send_event(AppExit::Success);
+/// step(&mut app, 1);
+/// assert!(app.world().resource::<BackendEvents>().0.is_empty());
+/// assert_eq!(app.world().resource::<PendingEvents>().0.len(), 1);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollConfig {
+    pub timeout: Duration,
+}
+
+impl Default for PollConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_millis(10),
+        }
+    }
+}
+
+/// Whether the events of the backend are read each frame. Enabled by default.
+///
+/// Pausing leaves the terminal's input alone, like while another part of the program reads a
+/// blocking prompt on it. No events are received while paused, and the events already buffered
+/// are received once resumed: by the terminal, by the background thread of
+/// [`TuiPlugin::threaded_input`], which keeps reading the terminal, or in [`PendingEvents`]. While
+/// paused, frames still wait for the [`PollConfig`] timeout.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// let mut app = App::new_tui_test(10, 1);
+/// app.world_mut().resource_mut::<EventPolling>().pause();
+/// app.send_test_event(event::Event::FocusLost);
+/// step(&mut app, 2);
+/// assert!(app.world().resource::<BackendEvents>().0.is_empty());
+/// assert_eq!(app.world().resource::<PendingEvents>().0.len(), 1);
+///
+/// app.world_mut().resource_mut::<EventPolling>().resume();
+/// step(&mut app, 1);
+/// assert_eq!(app.world().resource::<BackendEvents>().0, [event::Event::FocusLost]);
+/// ```
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventPolling {
+    pub enabled: bool,
+}
+
+impl Default for EventPolling {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+impl EventPolling {
+    /// Stop reading the events of the backend.
+    pub fn pause(&mut self) {
+        self.enabled = false;
+    }
+
+    /// Read the events of the backend again, starting with the ones buffered while paused.
+    pub fn resume(&mut self) {
+        self.enabled = true;
+    }
+}
+
+/// Whether the events of the backend are read this frame: not while [`EventPolling`] is paused,
+/// nor on the frame the app exits, so the exit doesn't wait for the [`PollConfig`] timeout.
+fn backend_events_read(polling: Res<EventPolling>, mut exits: EventReader<AppExit>) -> bool {
+    !exiting(&mut exits) && polling.enabled
+}
+
+/// Stand in for reading the events while they're not read, see [`backend_events_read`].
+fn skip_backend_events(
+    config: Res<PollConfig>,
+    mut exits: EventReader<AppExit>,
+    mut events: ResMut<BackendEvents>,
+) {
+    events.0.clear();
+    if !exiting(&mut exits) {
+        std::thread::sleep(config.timeout);
+    }
+}
+
+fn exiting(exits: &mut EventReader<AppExit>) -> bool {
+    exits.read().count() > 0
+}
+
+/// Systems running in [`Last`] on the frame an [`AppExit`] is sent, before the terminal is
+/// restored.
+///
+/// Add systems to this set to save state or flush files on exit while the terminal is still set
+/// up. They run once, however many [`AppExit`] events were sent, even over several frames. The
+/// terminal is restored right after them by the [`RestoreTerminal`] set, still in [`Last`], and
+/// the app stops at the end of the frame.
+///
+/// Only exits sent before [`Last`], or in [`Last`] before this set, are seen in time.
+///
+/// ```rust,no_run
+/// use ratatecs::prelude::*;
+///
+/// fn save() {
+///     // Write the state of the app to a file
+/// }
+///
+/// App::new()
+///     .add_plugins(RatatEcsPlugins::new())
+///     .add_systems(Last, save.in_set(Shutdown))
+///     .run();
+/// ```
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Shutdown;
+
+/// The system rendering the widgets pushed this frame, in [`PostUpdate`] unless changed with
+/// [`TuiPlugin::render_schedule`].
+///
+/// Widgets pushed in the same schedule are only rendered this frame when pushed before this
+/// set, like in [`PostUpdate`] with `.before(Render)`, otherwise they're dropped without being
+/// rendered. Widgets pushed by systems in earlier schedules, like [`Update`], always are.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Render;
+
+fn add_render<B: Backend + 'static>(app: &mut App, schedule: InternedScheduleLabel) {
+    app.add_systems(schedule, draw::render::<B>.in_set(Render));
+}
+
+/// Systems restoring the terminal on exit, after [`Shutdown`] and before [`AfterRestore`].
+///
+/// On the frame an [`AppExit`] is first seen, [`Last`] runs, in order:
+///
+/// 1. [`Shutdown`], for cleanups needing the terminal still set up, like saving state, or any
+///    system ordered with `.before(RestoreTerminal)`.
+/// 2. [`RestoreTerminal`], leaving the alternate screen and raw mode.
+/// 3. [`AfterRestore`], for cleanups printing to the normal terminal, like flushing logs to
+///    stderr so they stay on screen.
+///
+/// Prefer adding systems to [`Shutdown`] or [`AfterRestore`] rather than ordering them
+/// relative to this set, as these sets only run on exit.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RestoreTerminal;
+
+/// Systems running in [`Last`] on exit, once the terminal is restored, see [`RestoreTerminal`].
+///
+/// Like [`Shutdown`], they run once, however many [`AppExit`] events were sent.
+///
+/// ```rust
+/// # use std::sync::Mutex;
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// static ORDER: Mutex<Vec<&str>> = Mutex::new(Vec::new());
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.add_systems(Update, |mut exit: EventWriter<AppExit>| {
+///     exit.send(AppExit::Success);
+/// })
+/// .add_systems(
+///     Last,
+///     (
+///         (|| ORDER.lock().unwrap().push("flush logs")).in_set(AfterRestore),
+///         (|| ORDER.lock().unwrap().push("save")).in_set(Shutdown),
+///     ),
+/// );
+/// step(&mut app, 2);
+/// assert_eq!(*ORDER.lock().unwrap(), ["save", "flush logs"]);
+/// ```
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AfterRestore;
+
+/// Sent once the terminal was restored on exit, in [`Last`].
+///
+/// It's sent exactly once, on the first frame an [`AppExit`] is sent, even when the app keeps
+/// being updated and more exits are sent afterwards. With the test backends, there's nothing to
+/// restore but it's still sent.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// #[derive(Resource, Default)]
+/// struct Restored(u32);
+///
+/// fn exit(mut exit: EventWriter<AppExit>) {
+///     exit.send(AppExit::Success);
+/// }
+///
+/// fn count(mut restored: EventReader<TerminalRestored>, mut count: ResMut<Restored>) {
+///     count.0 += restored.read().count() as u32;
+/// }
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.init_resource::<Restored>();
+/// // Exit on every frame
+/// app.add_systems(Update, (exit, count));
+/// step(&mut app, 3);
+/// assert_eq!(app.world().resource::<Restored>().0, 1);
+/// ```
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalRestored;
+
+/// True on the first frame an [`AppExit`] is seen, and never again.
+fn first_exit(mut exits: EventReader<AppExit>, mut exited: Local<bool>) -> bool {
+    if *exited || exits.read().next().is_none() {
+        return false;
+    }
+    *exited = true;
+    true
+}
+
+fn send_terminal_restored(mut restored: EventWriter<TerminalRestored>) {
+    restored.send(TerminalRestored);
+}
+
+/// Systems filling [`BackendEvents`] with the events of the current backend.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+struct ReadBackendEvents;
+
+fn get_backend_events(config: Res<PollConfig>, mut events: ResMut<BackendEvents>) {
+    events.0.clear();
+
+    // Wait for the first event up to the poll window, then drain whatever else is pending
+    let mut timeout = config.timeout;
+    while crossterm::event::poll(timeout).unwrap_or(false) {
+        match crossterm::event::read() {
+            Ok(new_event) => events.0.push(new_event),
+            Err(_) => break,
+        }
+        timeout = Duration::ZERO;
+    }
+}
+
+/// Events to deliver through [`BackendEvents`] next frame, with the test backends.
+///
+/// Test backends don't read the real terminal, use [`AppExt::send_test_event`] to simulate
+/// input instead.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingEvents(pub VecDeque<crossterm::event::Event>);
+
+fn get_test_events(mut pending: ResMut<PendingEvents>, mut events: ResMut<BackendEvents>) {
+    events.0.clear();
+    events.0.extend(pending.0.drain(..));
+}
+
+fn update_backend_event(mut event: ResMut<BackendEvent>, events: Res<BackendEvents>) {
+    let new_event = events.0.last().cloned();
     match (event.0.is_some(), new_event.is_some()) {
         (_, true) => event.0 = new_event,
         (true, false) => event.0 = None,
@@ -82,53 +1457,315 @@ fn get_backend_events(mut event: ResMut<BackendEvent>) {
     }
 }
 
-fn cleanup_on_exit(_: NonSend<TerminalWrapper>, exits: EventReader<AppExit>) {
-    if !exits.is_empty() {
-        ratatui::restore();
+fn exit_on_ctrl_c(mut exit: EventWriter<AppExit>) {
+    exit.send(AppExit::Success);
+}
+
+/// A system exiting the app with [`AppExit::Error`] and `code`, for tools that fail. A `code` of
+/// `0` exits with `1`, so the process still fails.
+///
+/// The terminal is restored before [`App::run`] returns the exit code, which can be returned
+/// from `main` to exit the process with it.
+///
+/// ```rust
+/// # use std::num::NonZeroU8;
+/// # use std::sync::atomic::{AtomicBool, Ordering};
+/// # use ratatecs::prelude::*;
+/// static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+///
+/// fn shutdown() {
+///     SHUT_DOWN.store(true, Ordering::Relaxed);
+/// }
+///
+/// let exit = App::new()
+///     .add_plugins(RatatEcsPlugins::new().set(TuiPlugin::test_backend(10, 1)))
+///     .add_systems(Update, exit_with_error(3))
+///     .add_systems(Last, shutdown.in_set(Shutdown))
+///     .run();
+/// assert_eq!(exit, AppExit::Error(NonZeroU8::new(3).unwrap()));
+/// assert!(SHUT_DOWN.load(Ordering::Relaxed));
+/// ```
+pub fn exit_with_error(code: u8) -> impl FnMut(EventWriter<AppExit>) + Clone {
+    let code = NonZeroU8::new(code).unwrap_or(NonZeroU8::MIN);
+    move |mut exit: EventWriter<AppExit>| {
+        exit.send(AppExit::Error(code));
+    }
+}
+
+fn redraw_on_events(events: Res<BackendEvents>, mut redraw: ResMut<NeedsRedraw>) {
+    if !events.0.is_empty() {
+        redraw.set_if_neq(NeedsRedraw(true));
     }
 }
 
-pub struct ScopedWidget {
-    pub widget: Box<dyn WidgetRef>,
-    pub area: ratatui::prelude::Rect,
-    pub z_order: u32,
+fn redraw_on_state_change<S: States>(
+    mut transitions: EventReader<StateTransitionEvent<S>>,
+    redraw: Option<ResMut<NeedsRedraw>>,
+) {
+    if transitions.read().count() > 0 {
+        if let Some(mut redraw) = redraw {
+            redraw.set_if_neq(NeedsRedraw(true));
+        }
+    }
+}
+
+/// Current size of the terminal, as `(width, height)`.
+///
+/// Known from the start, before the first frame, then updated when the terminal is resized, and
+/// after each render.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// let app = App::new_tui_test(30, 7);
+/// assert_eq!(*app.world().resource::<TerminalSize>(), TerminalSize(30, 7));
+/// assert_eq!(app.world().resource::<FrameArea>().0, Rect::new(0, 0, 30, 7));
+/// ```
+///
+/// It's the size of the whole terminal, even when rendering to a smaller viewport:
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// # use ratatui::{backend::TestBackend, TerminalOptions, Viewport};
+/// let area = Rect::new(2, 2, 5, 3);
+/// let options = TerminalOptions { viewport: Viewport::Fixed(area) };
+/// let terminal = Terminal::with_options(TestBackend::new(20, 10), options).unwrap();
+/// let mut app = App::new();
+/// app.add_plugins(RatatEcsPlugins::new().set(TuiPlugin::with_terminal(terminal)));
+/// step(&mut app, 2);
+/// assert_eq!(*app.world().resource::<TerminalSize>(), TerminalSize(20, 10));
+/// assert_eq!(app.world().resource::<FrameArea>().0, area);
+/// ```
+///
+/// Resizes are coalesced: when several are received in a frame, like while dragging the corner
+/// of the window, only the last size is applied, resizing the terminal and resolving the
+/// [`layout`]s once.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::layout::LayoutRects;
+/// # use ratatecs::testing::step;
+/// #[derive(Resource, Default)]
+/// struct Recomputed(u32);
+///
+/// let mut app = App::new_tui_test(30, 7);
+/// app.init_resource::<Recomputed>();
+/// app.add_systems(
+///     Update,
+///     (|mut recomputed: ResMut<Recomputed>| recomputed.0 += 1)
+///         .run_if(resource_changed::<LayoutRects>),
+/// );
+/// step(&mut app, 1);
+///
+/// app.world_mut().resource_mut::<Recomputed>().0 = 0;
+/// for width in 31..36 {
+///     app.send_test_event(event::Event::Resize(width, 8));
+/// }
+/// step(&mut app, 3);
+/// assert_eq!(app.world().resource::<Recomputed>().0, 1);
+/// assert_eq!(*app.world().resource::<TerminalSize>(), TerminalSize(35, 8));
+/// ```
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TerminalSize(pub u16, pub u16);
+
+/// Run condition true when the terminal is at least `width` columns wide and `height` rows high,
+/// to only render a layout where it fits.
+///
+/// Use [`below_min_size`] for the opposite, like with [`render_too_small`] to show a message
+/// instead:
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{snapshot, step};
+/// # use ratatui::{backend::TestBackend, widgets::Paragraph};
+/// fn dashboard(mut drawer: WidgetDrawer<TestBackend>) {
+///     let area = drawer.area();
+///     drawer.push_widget(Box::new(Paragraph::new("dashboard")), area, 0);
+/// }
+///
+/// let mut app = App::new_tui_test(20, 4);
+/// app.add_systems(
+///     PostUpdate,
+///     (
+///         dashboard.run_if(min_size(20, 6)),
+///         render_too_small::<TestBackend>(20, 6),
+///     ),
+/// );
+/// step(&mut app, 1);
+/// assert_eq!(snapshot(&app), "\n Terminal too small\n  Resize it to 20x6\n");
+///
+/// app.send_test_event(event::Event::Resize(20, 6));
+/// step(&mut app, 2);
+/// assert!(snapshot(&app).starts_with("dashboard"));
+/// ```
+pub fn min_size(width: u16, height: u16) -> impl FnMut(Res<TerminalSize>) -> bool + Clone {
+    move |size: Res<TerminalSize>| size.0 >= width && size.1 >= height
 }
 
-pub struct WidgetsToDraw {
-    pub widgets: Vec<ScopedWidget>,
+/// Run condition true when the terminal is narrower than `width` columns or lower than `height`
+/// rows, the opposite of [`min_size`].
+pub fn below_min_size(width: u16, height: u16) -> impl FnMut(Res<TerminalSize>) -> bool + Clone {
+    move |size: Res<TerminalSize>| size.0 < width || size.1 < height
 }
 
-fn render(mut widget_drawer: WidgetDrawer) {
-    let _ = widget_drawer.terminal.terminal.draw(|frame| {
-        let buf = frame.buffer_mut();
-        widget_drawer.widgets.widgets.sort_by_key(|sw| sw.z_order);
-        for ScopedWidget { widget, area, .. } in widget_drawer.widgets.widgets.drain(..) {
-            widget.render_ref(area, buf);
+/// System rendering a message centered on the whole frame, asking to resize the terminal, when
+/// it's smaller than `width` by `height`, see [`min_size`].
+///
+/// The message is drawn over a cleared frame with the highest `z_order`, so it hides the widgets
+/// of the panels that still render when the terminal is too small.
+pub fn render_too_small<B: Backend + 'static>(
+    width: u16,
+    height: u16,
+) -> impl FnMut(Res<TerminalSize>, WidgetDrawer<B>) + Clone {
+    move |size: Res<TerminalSize>, mut drawer: WidgetDrawer<B>| {
+        if size.0 >= width && size.1 >= height {
+            return;
         }
-    });
+        let area = drawer.area();
+        let text = format!("Terminal too small\nResize it to {width}x{height}");
+        // The rows taken by the text, when wrapped on narrow terminals
+        let lines = text
+            .lines()
+            .map(|line| (line.len() as u16).div_ceil(area.width.max(1)))
+            .sum();
+        let paragraph = Paragraph::new(text).centered().wrap(Wrap { trim: true });
+        let [text_area] = Layout::vertical([Constraint::Length(lines)])
+            .flex(Flex::Center)
+            .areas(area);
+        drawer.push_widget(Box::new(Clear), area, u32::MAX);
+        drawer.push_widget(Box::new(paragraph), text_area, u32::MAX);
+    }
 }
 
-#[derive(SystemParam)]
-pub struct WidgetDrawer<'w> {
-    widgets: NonSendMut<'w, WidgetsToDraw>,
-    terminal: NonSendMut<'w, TerminalWrapper>,
+/// Apply the last resize of the frame, if any, ignoring the sizes it replaced.
+fn handle_resize<B: Backend + 'static>(
+    events: Res<BackendEvents>,
+    mut terminal: NonSendMut<TerminalWrapper<B>>,
+    mut size: ResMut<TerminalSize>,
+    mut frame_area: ResMut<FrameArea>,
+) {
+    let Some((width, height)) = last_resize(&events) else {
+        return;
+    };
+
+    let _ = terminal.terminal.resize(Rect::new(0, 0, width, height));
+    size.set_if_neq(TerminalSize(width, height));
+    frame_area.set_if_neq(FrameArea(terminal.terminal.get_frame().area()));
 }
 
-impl WidgetDrawer<'_> {
-    pub fn push_widget(
-        &mut self,
-        widget: Box<dyn WidgetRef>,
-        area: ratatui::prelude::Rect,
-        z_order: u32,
-    ) {
-        self.widgets.widgets.push(ScopedWidget {
-            widget,
-            area,
-            z_order,
-        });
+fn last_resize(events: &BackendEvents) -> Option<(u16, u16)> {
+    events.0.iter().rev().find_map(|event| match event {
+        crossterm::event::Event::Resize(width, height) => Some((*width, *height)),
+        _ => None,
+    })
+}
+
+/// Resize the test backend like a real terminal would be, so the terminal doesn't go back to
+/// the size of the backend when rendering.
+fn resize_test_backend(
+    events: Res<BackendEvents>,
+    mut terminal: NonSendMut<TerminalWrapper<TestBackend>>,
+) {
+    if let Some((width, height)) = last_resize(&events) {
+        terminal.terminal.backend_mut().resize(width, height);
+    }
+}
+
+/// Every frame rendered with [`BackendKind::Record`], oldest first.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordedFrames(pub Vec<Buffer>);
+
+impl RecordedFrames {
+    /// The recorded frames as text with ANSI escape codes, see [`export::to_ansi`].
+    pub fn to_ansi_strings(&self) -> Vec<String> {
+        self.0.iter().map(export::to_ansi).collect()
     }
+}
+
+fn record_frame(
+    terminal: NonSend<TerminalWrapper<TestBackend>>,
+    mut frames: ResMut<RecordedFrames>,
+) {
+    frames.0.push(terminal.terminal.backend().buffer().clone());
+}
+
+fn flush_on_exit<B: Backend + 'static>(mut terminal: NonSendMut<TerminalWrapper<B>>) {
+    let _ = terminal.terminal.backend_mut().flush();
+}
+
+/// Errors while restoring the terminal, printed once it's restored so they aren't written over
+/// the frame, or in the alternate screen that's left right after.
+#[derive(Resource, Default)]
+struct ExitErrors(Vec<String>);
+
+fn cleanup_on_exit(mut terminal: NonSendMut<TerminalWrapper>, mut errors: ResMut<ExitErrors>) {
+    // Write what is still buffered before leaving the alternate screen
+    if let Err(err) = Backend::flush(terminal.terminal.backend_mut()) {
+        errors.0.push(format!("Failed to flush terminal: {err}"));
+    }
+    terminal::restore();
+    for error in errors.0.drain(..) {
+        eprintln!("{error}");
+    }
+}
+
+/// Leave the last frame rendered on the normal screen in the scrollback, with the cursor on the
+/// line below it for the shell.
+fn move_below_frame_on_exit(
+    mut terminal: NonSendMut<TerminalWrapper>,
+    mut errors: ResMut<ExitErrors>,
+) {
+    if let Err(err) = try_move_below_frame(&mut terminal.terminal) {
+        errors
+            .0
+            .push(format!("Failed to move the cursor below the frame: {err}"));
+    }
+}
+
+fn try_move_below_frame(terminal: &mut Terminal<DefaultBackend>) -> io::Result<()> {
+    let bottom = terminal.get_frame().area().bottom().saturating_sub(1);
+    terminal.set_cursor_position((0, bottom))?;
+    terminal.show_cursor()?;
+    // Raw mode may still be enabled, so the line feed doesn't return to the first column
+    terminal.backend_mut().write_all(b"\r\n")?;
+    Backend::flush(terminal.backend_mut())
+}
+
+/// Clear the lines of a viewport that isn't fullscreen, leaving the cursor where they started for
+/// an inline viewport.
+fn clear_viewport_on_exit(
+    mut terminal: NonSendMut<TerminalWrapper>,
+    mut errors: ResMut<ExitErrors>,
+) {
+    if let Err(err) = terminal.terminal.clear() {
+        errors.0.push(format!("Failed to clear terminal: {err}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    #[test]
+    fn test_backend_leaves_the_real_terminal_alone() {
+        let mut app = App::new_tui_test(10, 1);
+        app.add_systems(Update, |mut exit: EventWriter<AppExit>| {
+            exit.send(AppExit::Success);
+        });
+        let schedule = app.get_schedule(Last).unwrap();
+        let restores: Vec<_> = schedule
+            .graph()
+            .systems()
+            .map(|(_, system, _)| system.name())
+            .filter(|name| name.contains("on_exit"))
+            .collect();
+        assert!(restores.is_empty(), "restoring systems: {restores:?}");
 
-    pub fn get_frame(&mut self) -> Frame {
-        self.terminal.terminal.get_frame()
+        testing::step(&mut app, 1);
+        assert!(app.should_exit().is_some());
+        assert!(terminal::enabled_modes().is_none());
+        assert_eq!(terminal::LEAVE_CALLS.load(Ordering::SeqCst), 0);
     }
 }
@@ -0,0 +1,151 @@
+//! Mouse gestures tracked across frames, from the mouse events of the terminal.
+//!
+//! The mouse must be captured with [`TuiPlugin::enable_mouse`](crate::TuiPlugin::enable_mouse)
+//! for the terminal to report mouse events.
+
+use std::time::{Duration, Instant};
+
+use bevy_ecs::system::{Res, ResMut, Resource};
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+
+use crate::BackendEvents;
+
+/// Double-clicks and drags with the left button, as of the last frame.
+///
+/// Two clicks count as a double-click when they happen on the same cell, within the double-click
+/// threshold of each other. The threshold can be changed by inserting the resource after adding
+/// the plugins:
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// use event::{MouseButton, MouseEvent, MouseEventKind};
+///
+/// let mut app = App::new_tui_test(10, 5);
+/// app.insert_resource(MouseState::new().double_click_threshold(Duration::from_secs(1)));
+///
+/// let click = MouseEvent {
+///     kind: MouseEventKind::Down(MouseButton::Left),
+///     column: 3,
+///     row: 2,
+///     modifiers: event::KeyModifiers::NONE,
+/// };
+/// app.send_test_event(event::Event::Mouse(click));
+/// app.send_test_event(event::Event::Mouse(click));
+/// step(&mut app, 1);
+/// assert_eq!(app.world().resource::<MouseState>().just_double_clicked(), Some((3, 2)));
+///
+/// let drag = MouseEvent {
+///     kind: MouseEventKind::Drag(MouseButton::Left),
+///     column: 6,
+///     row: 1,
+///     ..click
+/// };
+/// app.send_test_event(event::Event::Mouse(drag));
+/// step(&mut app, 1);
+/// let mouse = app.world().resource::<MouseState>();
+/// assert_eq!(mouse.just_double_clicked(), None);
+/// assert_eq!(mouse.drag_delta(), Some((3, -1)));
+/// ```
+#[derive(Resource, Debug, Clone)]
+pub struct MouseState {
+    double_click_threshold: Duration,
+    last_click: Option<((u16, u16), Instant)>,
+    double_clicked: Option<(u16, u16)>,
+    /// Position of the mouse while the left button is held down.
+    drag_position: Option<(u16, u16)>,
+    drag_delta: Option<(i16, i16)>,
+}
+
+impl Default for MouseState {
+    fn default() -> Self {
+        Self {
+            double_click_threshold: Self::DEFAULT_DOUBLE_CLICK_THRESHOLD,
+            last_click: None,
+            double_clicked: None,
+            drag_position: None,
+            drag_delta: None,
+        }
+    }
+}
+
+impl MouseState {
+    pub const DEFAULT_DOUBLE_CLICK_THRESHOLD: Duration = Duration::from_millis(400);
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The longest time between two clicks counting as a double-click.
+    pub fn double_click_threshold(mut self, threshold: Duration) -> Self {
+        self.double_click_threshold = threshold;
+        self
+    }
+
+    /// The cell double-clicked during the last frame, as column and row.
+    ///
+    /// A third click doesn't count as another double-click, but starts a new one.
+    pub fn just_double_clicked(&self) -> Option<(u16, u16)> {
+        self.double_clicked
+    }
+
+    /// How far the mouse was dragged during the last frame, in columns and rows. `None` when it
+    /// didn't move with the left button held down.
+    pub fn drag_delta(&self) -> Option<(i16, i16)> {
+        self.drag_delta
+    }
+
+    /// Whether the left button is held down since it was pressed inside the terminal.
+    pub fn is_dragging(&self) -> bool {
+        self.drag_position.is_some()
+    }
+
+    fn update(&mut self, event: &crossterm::event::MouseEvent, now: Instant) {
+        let position = (event.column, event.row);
+        match event.kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                match self.last_click.take() {
+                    Some((last_position, at))
+                        if last_position == position
+                            && now.duration_since(at) <= self.double_click_threshold =>
+                    {
+                        self.double_clicked = Some(position);
+                    }
+                    _ => self.last_click = Some((position, now)),
+                }
+                self.drag_position = Some(position);
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                if let Some((column, row)) = self.drag_position.replace(position) {
+                    let (delta_column, delta_row) = self.drag_delta.unwrap_or_default();
+                    self.drag_delta = Some((
+                        delta_column.saturating_add(offset(column, event.column)),
+                        delta_row.saturating_add(offset(row, event.row)),
+                    ));
+                }
+            }
+            MouseEventKind::Up(MouseButton::Left) => self.drag_position = None,
+            _ => (),
+        }
+    }
+}
+
+fn offset(from: u16, to: u16) -> i16 {
+    (i32::from(to) - i32::from(from)).clamp(i16::MIN.into(), i16::MAX.into()) as i16
+}
+
+pub(crate) fn update_mouse_state(events: Res<BackendEvents>, mut mouse: ResMut<MouseState>) {
+    // Only touch the resource when something happens, to keep its change detection meaningful
+    if mouse.double_clicked.is_some() || mouse.drag_delta.is_some() {
+        mouse.double_clicked = None;
+        mouse.drag_delta = None;
+    }
+
+    let now = Instant::now();
+    for event in &events.0 {
+        if let Event::Mouse(mouse_event) = event {
+            mouse.update(mouse_event, now);
+        }
+    }
+}
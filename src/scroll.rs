@@ -0,0 +1,117 @@
+//! Scrolling content with the mouse wheel.
+
+use bevy_ecs::{
+    component::Component,
+    system::{NonSend, Query, Res},
+};
+use crossterm::event::{Event, MouseEventKind};
+
+use crate::{BackendEvents, WidgetId, WidgetsToDraw};
+
+/// How far a scrollable region is scrolled, changed by the mouse wheel over its widget.
+///
+/// Attach it to the entity of a scrollable region, and set [`ScrollState::widget`] to the id of
+/// the widget showing the region each time it's pushed, like with
+/// [`WidgetDrawer::push_scrollable`](crate::WidgetDrawer::push_scrollable). Each wheel event over
+/// the widget, as found by [`WidgetsToDraw::hit_test`], scrolls by a row. The mouse must be
+/// captured with [`TuiPlugin::enable_mouse`](crate::TuiPlugin::enable_mouse).
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{snapshot, step};
+/// # use ratatui::{backend::TestBackend, layout::Size, widgets::Paragraph};
+/// use event::{MouseEvent, MouseEventKind};
+///
+/// fn logs(mut drawer: WidgetDrawer<TestBackend>, mut scroll: Query<&mut ScrollState>) {
+///     let mut scroll = scroll.single_mut();
+///     let lines = Paragraph::new("line 1\nline 2\nline 3\nline 4");
+///     let offset = Position::new(0, scroll.offset);
+///     let area = Rect::new(0, 0, 6, 2);
+///     let widget = drawer.push_scrollable(Box::new(lines), Size::new(6, 4), offset, area, 0);
+///     scroll.widget = Some(widget);
+/// }
+///
+/// let wheel = |kind, row| {
+///     event::Event::Mouse(MouseEvent {
+///         kind,
+///         column: 1,
+///         row,
+///         modifiers: event::KeyModifiers::NONE,
+///     })
+/// };
+///
+/// let mut app = App::new_tui_test(6, 3);
+/// app.world_mut().spawn(ScrollState::new(2));
+/// app.add_systems(PostUpdate, logs);
+///
+/// // The offset is clamped to the end of the content
+/// for _ in 0..3 {
+///     app.send_test_event(wheel(MouseEventKind::ScrollDown, 1));
+/// }
+/// step(&mut app, 2);
+/// assert_eq!(snapshot(&app), "line 3\nline 4\n");
+///
+/// // Scrolling outside of the widget does nothing
+/// app.send_test_event(wheel(MouseEventKind::ScrollUp, 2));
+/// step(&mut app, 2);
+/// assert_eq!(snapshot(&app), "line 3\nline 4\n");
+///
+/// app.send_test_event(wheel(MouseEventKind::ScrollUp, 0));
+/// step(&mut app, 2);
+/// assert_eq!(snapshot(&app), "line 2\nline 3\n");
+/// ```
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ScrollState {
+    /// The first row shown, between `0` and `max`.
+    pub offset: u16,
+    /// The largest offset, usually the height of the content minus the height of its area.
+    pub max: u16,
+    /// The widget showing the region this frame, scrolled when the wheel is used over it.
+    pub widget: Option<WidgetId>,
+}
+
+impl ScrollState {
+    pub fn new(max: u16) -> Self {
+        Self {
+            max,
+            ..Self::default()
+        }
+    }
+
+    /// Scroll by `rows`, down for positive rows, up for negative ones, staying in `[0, max]`.
+    pub fn scroll_by(&mut self, rows: i32) {
+        let offset = (i32::from(self.offset) + rows).clamp(0, i32::from(self.max));
+        self.offset = offset as u16;
+    }
+
+    /// Change the largest offset, like when the content grows, moving the offset into range.
+    pub fn set_max(&mut self, max: u16) {
+        self.max = max;
+        self.offset = self.offset.min(max);
+    }
+}
+
+pub(crate) fn scroll_on_wheel(
+    events: Res<BackendEvents>,
+    widgets: NonSend<WidgetsToDraw>,
+    mut scrolls: Query<&mut ScrollState>,
+) {
+    for event in &events.0 {
+        let Event::Mouse(mouse_event) = event else {
+            continue;
+        };
+        let rows = match mouse_event.kind {
+            MouseEventKind::ScrollDown => 1,
+            MouseEventKind::ScrollUp => -1,
+            _ => continue,
+        };
+        let Some(hit) = widgets.hit_test(mouse_event.column, mouse_event.row) else {
+            continue;
+        };
+        for mut scroll in &mut scrolls {
+            if scroll.widget == Some(hit) {
+                scroll.scroll_by(rows);
+            }
+        }
+    }
+}
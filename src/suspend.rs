@@ -0,0 +1,80 @@
+//! Suspend the app on Ctrl+Z, and take over the terminal again when it's resumed.
+//!
+//! Raw mode disables the terminal's own handling of Ctrl+Z, so the key is received like any
+//! other. It's handled here the way a shell job would be: the terminal is restored, the process
+//! stops itself, and once resumed with `fg` the terminal is set up again and fully redrawn.
+//! A `SIGTSTP` sent from outside, like with `kill -TSTP`, is handled the same way.
+
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use bevy_ecs::{
+    event::EventWriter,
+    system::{NonSendMut, Res, ResMut, Resource},
+};
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use signal_hook::{consts::SIGTSTP, low_level};
+
+use crate::{
+    input::is_key_press, terminal, BackendEvents, NeedsRedraw, RenderError, TerminalWrapper,
+};
+
+/// Set by the `SIGTSTP` handler.
+#[derive(Resource)]
+pub(crate) struct SuspendSignal(Arc<AtomicBool>);
+
+impl SuspendSignal {
+    /// The signal, along with whether the handler was registered. Ctrl+Z still suspends the app
+    /// when it wasn't.
+    pub(crate) fn register() -> (Self, io::Result<()>) {
+        let received = Arc::new(AtomicBool::new(false));
+        let registered = signal_hook::flag::register(SIGTSTP, received.clone()).map(|_| ());
+        (Self(received), registered)
+    }
+}
+
+pub(crate) fn suspend_on_ctrl_z(
+    mut terminal: NonSendMut<TerminalWrapper>,
+    signal: Res<SuspendSignal>,
+    events: Res<BackendEvents>,
+    redraw: Option<ResMut<NeedsRedraw>>,
+    mut errors: EventWriter<RenderError>,
+) {
+    let ctrl_z = events.0.iter().any(|event| {
+        matches!(event, Event::Key(key_event)
+            if is_key_press(key_event)
+                && key_event.code == KeyCode::Char('z')
+                && key_event.modifiers.contains(KeyModifiers::CONTROL))
+    });
+    if !signal.0.swap(false, Ordering::Relaxed) && !ctrl_z {
+        return;
+    }
+
+    // Not printed, as the terminal may still be set up, and draw again even if it failed so the
+    // screen isn't left half restored
+    if let Err(err) = suspend() {
+        errors.send(RenderError(err));
+    }
+    // The screen was lost while suspended, draw everything again
+    if let Err(err) = terminal.terminal.clear() {
+        errors.send(RenderError(err));
+    }
+    if let Some(mut redraw) = redraw {
+        redraw.0 = true;
+    }
+}
+
+/// Restore the terminal, stop until resumed, then set the terminal up again.
+fn suspend() -> io::Result<()> {
+    let Some(modes) = terminal::leave()? else {
+        return Ok(());
+    };
+    // The handler replaced the default action of stopping the process, run it now
+    low_level::emulate_default_handler(SIGTSTP)?;
+    terminal::enter(modes)
+}
@@ -0,0 +1,128 @@
+//! Background tasks, like network requests, updating the world when they complete without
+//! blocking the frame loop.
+//!
+//! Tasks are spawned on the [`AsyncComputeTaskPool`], so futures needing a specific runtime,
+//! like tokio, must enter it themselves. Without the `multi_threaded` feature of `bevy_tasks`,
+//! which ratatecs doesn't enable, the pool runs them on the main thread: they're polled when
+//! spawned and at the start of each frame, so they must wait on asynchronous I/O or timers rather
+//! than block. With the feature enabled, like by an app also depending on `bevy`, they run on the
+//! threads of the pool.
+//!
+//! The results are applied to the world at the start of the first frame after the task
+//! completes, before [`Update`](bevy_app::Update). As the frame loop waits for terminal events up
+//! to the poll timeout, see [`PollConfig`](crate::PollConfig), that's at most a poll timeout later.
+
+use std::{future::Future, sync::Mutex};
+
+use bevy_ecs::{system::Resource, world::World};
+use bevy_tasks::{block_on, poll_once, AsyncComputeTaskPool, Task, TaskPool};
+
+use crate::NeedsRedraw;
+
+/// A spawned task, applying its output to the world once it completed. Returns whether it did.
+type PollTask = Box<dyn FnMut(&mut World) -> bool + Send>;
+
+/// Spawn background tasks whose output updates the world once they complete.
+///
+/// A redraw is requested when results are applied, when rendering only when needed with
+/// [`TuiPlugin::render_when_dirty`](crate::TuiPlugin::render_when_dirty).
+///
+/// ```rust
+/// # use std::time::Duration;
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// #[derive(Resource)]
+/// struct Weather(String);
+///
+/// fn fetch(tasks: Res<BackgroundTasks>) {
+///     tasks.spawn_into(async {
+///         // Waiting on a response, polled again next frame
+///         bevy_tasks::futures_lite::future::yield_now().await;
+///         Weather("sunny".to_string())
+///     });
+/// }
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.add_systems(Startup, fetch);
+/// while app.world().get_resource::<Weather>().is_none() {
+///     step(&mut app, 1);
+///     std::thread::sleep(Duration::from_millis(1));
+/// }
+/// assert_eq!(app.world().resource::<Weather>().0, "sunny");
+/// assert_eq!(app.world().resource::<BackgroundTasks>().running(), 0);
+/// ```
+#[derive(Resource, Default)]
+pub struct BackgroundTasks {
+    tasks: Mutex<Vec<PollTask>>,
+}
+
+impl BackgroundTasks {
+    /// Run `future` in the background, then call `apply` with its output and the world.
+    pub fn spawn<T: Send + 'static>(
+        &self,
+        future: impl Future<Output = T> + Send + 'static,
+        apply: impl FnOnce(T, &mut World) + Send + 'static,
+    ) {
+        let mut task: Task<T> = AsyncComputeTaskPool::get_or_init(TaskPool::default).spawn(future);
+        let mut apply = Some(apply);
+        let poll: PollTask = Box::new(move |world| {
+            let Some(output) = block_on(poll_once(&mut task)) else {
+                return false;
+            };
+            if let Some(apply) = apply.take() {
+                apply(output, world);
+            }
+            true
+        });
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.push(poll);
+        }
+    }
+
+    /// Run `future` in the background, then insert its output as a resource, replacing the
+    /// previous one.
+    pub fn spawn_into<R: Resource>(&self, future: impl Future<Output = R> + Send + 'static) {
+        self.spawn(future, |resource, world| {
+            world.insert_resource(resource);
+        });
+    }
+
+    /// The number of tasks that didn't complete yet.
+    pub fn running(&self) -> usize {
+        self.tasks.lock().map_or(0, |tasks| tasks.len())
+    }
+}
+
+/// Apply the results of the tasks that completed since the last frame.
+pub(crate) fn poll_tasks(world: &mut World) {
+    let Some(mut tasks) = world
+        .resource::<BackgroundTasks>()
+        .tasks
+        .lock()
+        .ok()
+        .map(|mut tasks| std::mem::take(&mut *tasks))
+    else {
+        return;
+    };
+    if tasks.is_empty() {
+        return;
+    }
+    // Make progress on the tasks run by the main thread, without the threads of the pool
+    if let Some(pool) = AsyncComputeTaskPool::try_get() {
+        pool.with_local_executor(|executor| while executor.try_tick() {});
+    }
+
+    let count = tasks.len();
+    tasks.retain_mut(|poll| !poll(world));
+    let applied = tasks.len() < count;
+    // Keep the tasks spawned while applying the results
+    if let Ok(mut spawned) = world.resource::<BackgroundTasks>().tasks.lock() {
+        tasks.append(&mut spawned);
+        *spawned = tasks;
+    }
+    if applied {
+        if let Some(mut redraw) = world.get_resource_mut::<NeedsRedraw>() {
+            redraw.0 = true;
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Setup and teardown of the real terminal.
+
+use std::{
+    io::{self, BufWriter, Stdout, Write},
+    sync::{Mutex, Once},
+};
+
+use crossterm::{
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
+};
+use ratatui::{layout::Rect, prelude::CrosstermBackend, Terminal, TerminalOptions, Viewport};
+
+use crate::{DefaultBackend, WriterBackend};
+
+/// Optional terminal modes enabled on init.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Modes {
+    pub raw_mode: bool,
+    pub mouse_capture: bool,
+    pub bracketed_paste: bool,
+    pub focus_change: bool,
+    /// Pushed only if the terminal supports them, empty when it doesn't.
+    pub keyboard_enhancement: KeyboardEnhancementFlags,
+    /// Only rendering to the whole screen uses the alternate screen.
+    pub alternate_screen: bool,
+}
+
+impl Default for Modes {
+    fn default() -> Self {
+        Self {
+            raw_mode: true,
+            mouse_capture: false,
+            bracketed_paste: false,
+            focus_change: false,
+            keyboard_enhancement: KeyboardEnhancementFlags::empty(),
+            alternate_screen: true,
+        }
+    }
+}
+
+/// The modes enabled by the last init, to disable them on restore, including from the panic hook.
+/// `None` when the terminal isn't set up, so that it's only restored once, and never restored by
+/// apps that didn't set it up, like with the test backends.
+static ENABLED_MODES: Mutex<Option<Modes>> = Mutex::new(None);
+
+/// How many times [`leave`] was called, for tests to check that the real terminal is left alone.
+#[cfg(test)]
+pub(crate) static LEAVE_CALLS: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(0);
+
+/// Large enough for most frames to be written to the terminal at once.
+const OUTPUT_CAPACITY: usize = 64 * 1024;
+
+/// The standard output written by [`DefaultBackend`], buffered with
+/// [`TuiPlugin::buffered_output`](crate::TuiPlugin::buffered_output).
+pub struct TerminalOutput(Output);
+
+enum Output {
+    Unbuffered(Stdout),
+    Buffered(BufWriter<Stdout>),
+}
+
+impl TerminalOutput {
+    fn new(buffered: bool) -> Self {
+        Self(if buffered {
+            Output::Buffered(BufWriter::with_capacity(OUTPUT_CAPACITY, io::stdout()))
+        } else {
+            Output::Unbuffered(io::stdout())
+        })
+    }
+}
+
+impl Write for TerminalOutput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.0 {
+            Output::Unbuffered(stdout) => stdout.write(buf),
+            Output::Buffered(writer) => writer.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.0 {
+            Output::Unbuffered(stdout) => stdout.flush(),
+            Output::Buffered(writer) => writer.flush(),
+        }
+    }
+}
+
+pub(crate) fn init(modes: Modes, viewport: Viewport, buffered: bool) -> Terminal<DefaultBackend> {
+    install_panic_hook();
+    try_init(modes, viewport, buffered).expect("failed to initialize terminal")
+}
+
+fn try_init(
+    modes: Modes,
+    viewport: Viewport,
+    buffered: bool,
+) -> io::Result<Terminal<DefaultBackend>> {
+    enter(modes)?;
+    Terminal::with_options(
+        CrosstermBackend::new(TerminalOutput::new(buffered)),
+        TerminalOptions { viewport },
+    )
+}
+
+/// Enable the modes, and remember them for [`leave`].
+pub(crate) fn enter(mut modes: Modes) -> io::Result<()> {
+    if modes.raw_mode {
+        enable_raw_mode()?;
+    }
+    if modes.alternate_screen {
+        execute!(io::stdout(), EnterAlternateScreen)?;
+    }
+    if modes.mouse_capture {
+        execute!(io::stdout(), EnableMouseCapture)?;
+    }
+    if modes.bracketed_paste {
+        execute!(io::stdout(), EnableBracketedPaste)?;
+    }
+    if modes.focus_change {
+        execute!(io::stdout(), EnableFocusChange)?;
+    }
+    if !modes.keyboard_enhancement.is_empty() {
+        // Querying the terminal needs raw mode to read its answer, and is only done once the
+        // modes are enabled, before the input thread starts reading events
+        if supports_keyboard_enhancement().unwrap_or(false) {
+            execute!(
+                io::stdout(),
+                PushKeyboardEnhancementFlags(modes.keyboard_enhancement)
+            )?;
+        } else {
+            modes.keyboard_enhancement = KeyboardEnhancementFlags::empty();
+        }
+    }
+    if let Ok(mut enabled) = ENABLED_MODES.lock() {
+        *enabled = Some(modes);
+    }
+    Ok(())
+}
+
+/// The modes enabled by the last init, with the keyboard enhancement flags the terminal supports.
+pub(crate) fn enabled_modes() -> Option<Modes> {
+    ENABLED_MODES.lock().ok().and_then(|enabled| *enabled)
+}
+
+pub(crate) fn init_writer(writer: Box<dyn io::Write + Send>) -> Terminal<WriterBackend> {
+    // The writer may not be a terminal and can't be queried, so size the output like the
+    // controlling terminal if there is one, and fall back to 80x24 otherwise
+    let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+    Terminal::with_options(
+        CrosstermBackend::new(writer),
+        TerminalOptions {
+            viewport: Viewport::Fixed(Rect::new(0, 0, width, height)),
+        },
+    )
+    .expect("failed to initialize terminal")
+}
+
+pub(crate) fn restore() {
+    if let Err(err) = try_restore() {
+        eprintln!("Failed to restore terminal: {err}");
+    }
+}
+
+fn try_restore() -> io::Result<()> {
+    leave().map(|_| ())
+}
+
+/// Go back to the normal terminal, returning the modes that were enabled, or `None` if it was
+/// already restored.
+///
+/// When a mode can't be disabled, the others still are and the first error is returned. The
+/// modes that are still enabled are kept, so restoring again, like from the panic hook, retries
+/// them.
+pub(crate) fn leave() -> io::Result<Option<Modes>> {
+    #[cfg(test)]
+    LEAVE_CALLS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    // Take the modes so the terminal is only restored once, even if restoring again after a panic
+    let Some(modes) = ENABLED_MODES
+        .lock()
+        .ok()
+        .and_then(|mut enabled| enabled.take())
+    else {
+        return Ok(None);
+    };
+
+    // Disable every mode even if one fails, keeping the ones that failed to restore them again
+    let mut error = None;
+    let keyboard_enhancement = disable(!modes.keyboard_enhancement.is_empty(), &mut error, || {
+        execute!(io::stdout(), PopKeyboardEnhancementFlags)
+    });
+    let still_enabled = Modes {
+        keyboard_enhancement: if keyboard_enhancement {
+            modes.keyboard_enhancement
+        } else {
+            KeyboardEnhancementFlags::empty()
+        },
+        mouse_capture: disable(modes.mouse_capture, &mut error, || {
+            execute!(io::stdout(), DisableMouseCapture)
+        }),
+        bracketed_paste: disable(modes.bracketed_paste, &mut error, || {
+            execute!(io::stdout(), DisableBracketedPaste)
+        }),
+        focus_change: disable(modes.focus_change, &mut error, || {
+            execute!(io::stdout(), DisableFocusChange)
+        }),
+        raw_mode: disable(modes.raw_mode, &mut error, disable_raw_mode),
+        alternate_screen: disable(modes.alternate_screen, &mut error, || {
+            execute!(io::stdout(), LeaveAlternateScreen)
+        }),
+    };
+    match error {
+        None => Ok(Some(modes)),
+        Some(err) => {
+            if let Ok(mut enabled) = ENABLED_MODES.lock() {
+                *enabled = Some(still_enabled);
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Run `leave` if the mode is `enabled`, returning whether it's still enabled, and keep the
+/// first error in `error`.
+fn disable(
+    enabled: bool,
+    error: &mut Option<io::Error>,
+    leave: impl FnOnce() -> io::Result<()>,
+) -> bool {
+    if !enabled {
+        return false;
+    }
+    match leave() {
+        Ok(()) => false,
+        Err(err) => {
+            error.get_or_insert(err);
+            true
+        }
+    }
+}
+
+/// Restore the terminal before the previously installed panic hook prints the panic message.
+///
+/// The hook is only installed once, however many times the plugin is built.
+fn install_panic_hook() {
+    static PANIC_HOOK: Once = Once::new();
+
+    PANIC_HOOK.call_once(|| {
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            restore();
+            hook(info);
+        }));
+    });
+}
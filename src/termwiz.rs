@@ -0,0 +1,191 @@
+//! Setup, teardown and events of the terminal through termwiz.
+
+use bevy_ecs::system::{Local, NonSendMut, Res, ResMut};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::{
+    backend::TermwizBackend,
+    termwiz::{
+        input::{self, InputEvent, Modifiers, MouseButtons},
+        terminal::Terminal as _,
+    },
+    Terminal,
+};
+
+use crate::{BackendEvents, PollConfig, TerminalWrapper};
+
+pub(crate) fn init() -> Terminal<TermwizBackend> {
+    let backend = TermwizBackend::new().expect("failed to initialize termwiz backend");
+    Terminal::new(backend).expect("failed to initialize terminal")
+}
+
+pub(crate) fn cleanup_on_exit(mut terminal: NonSendMut<TerminalWrapper<TermwizBackend>>) {
+    let terminal = terminal
+        .terminal
+        .backend_mut()
+        .buffered_terminal_mut()
+        .terminal();
+    if let Err(err) = terminal
+        .set_cooked_mode()
+        .and_then(|_| terminal.exit_alternate_screen())
+    {
+        eprintln!("Failed to restore terminal: {err}");
+    }
+}
+
+pub(crate) fn get_backend_events(
+    config: Res<PollConfig>,
+    mut terminal: NonSendMut<TerminalWrapper<TermwizBackend>>,
+    mut events: ResMut<BackendEvents>,
+    mut held_buttons: Local<MouseButtons>,
+) {
+    events.0.clear();
+
+    let terminal = terminal
+        .terminal
+        .backend_mut()
+        .buffered_terminal_mut()
+        .terminal();
+
+    // Wait for the first event up to the poll window, then drain whatever else is pending
+    let mut timeout = config.timeout;
+    while let Ok(Some(input)) = terminal.poll_input(Some(timeout)) {
+        events
+            .0
+            .extend(to_crossterm_events(input, &mut held_buttons));
+        timeout = std::time::Duration::ZERO;
+    }
+}
+
+/// Translate a termwiz input into the crossterm events used everywhere else.
+///
+/// `held_buttons` are the mouse buttons held as of the previous mouse event.
+fn to_crossterm_events(input: InputEvent, held_buttons: &mut MouseButtons) -> Vec<Event> {
+    let event = match input {
+        InputEvent::Key(key) => to_key_code(key.key)
+            .map(|code| Event::Key(KeyEvent::new(code, to_key_modifiers(key.modifiers)))),
+        InputEvent::Mouse(mouse) => return to_mouse_events(mouse, held_buttons),
+        InputEvent::Resized { cols, rows } => Some(Event::Resize(cols as u16, rows as u16)),
+        InputEvent::Paste(text) => Some(Event::Paste(text)),
+        InputEvent::PixelMouse(_) | InputEvent::Wake => None,
+    };
+    event.into_iter().collect()
+}
+
+/// termwiz reports the buttons held rather than when they're pressed and released, so compare
+/// them with the buttons held before to send presses, drags and releases like crossterm.
+fn to_mouse_events(mouse: input::MouseEvent, held_buttons: &mut MouseButtons) -> Vec<Event> {
+    let event = |kind| {
+        // termwiz reports 1-based coordinates
+        Event::Mouse(MouseEvent {
+            kind,
+            column: mouse.x.saturating_sub(1),
+            row: mouse.y.saturating_sub(1),
+            modifiers: to_key_modifiers(mouse.modifiers),
+        })
+    };
+
+    let positive = mouse.mouse_buttons.contains(MouseButtons::WHEEL_POSITIVE);
+    if mouse.mouse_buttons.contains(MouseButtons::VERT_WHEEL) {
+        return vec![event(if positive {
+            MouseEventKind::ScrollUp
+        } else {
+            MouseEventKind::ScrollDown
+        })];
+    }
+    if mouse.mouse_buttons.contains(MouseButtons::HORZ_WHEEL) {
+        return vec![event(if positive {
+            MouseEventKind::ScrollLeft
+        } else {
+            MouseEventKind::ScrollRight
+        })];
+    }
+
+    let buttons = [
+        (MouseButtons::LEFT, MouseButton::Left),
+        (MouseButtons::RIGHT, MouseButton::Right),
+        (MouseButtons::MIDDLE, MouseButton::Middle),
+    ];
+    let mut events = Vec::new();
+    let mut now_held = MouseButtons::NONE;
+    for (flag, button) in buttons {
+        let held = mouse.mouse_buttons.contains(flag.clone());
+        let kind = match (held_buttons.contains(flag.clone()), held) {
+            (false, true) => MouseEventKind::Down(button),
+            (true, true) => MouseEventKind::Drag(button),
+            (true, false) => MouseEventKind::Up(button),
+            (false, false) => continue,
+        };
+        if held {
+            now_held |= flag;
+        }
+        events.push(event(kind));
+    }
+    *held_buttons = now_held;
+    if events.is_empty() {
+        events.push(event(MouseEventKind::Moved));
+    }
+    events
+}
+
+fn to_key_code(key: input::KeyCode) -> Option<KeyCode> {
+    Some(match key {
+        input::KeyCode::Char(c) => KeyCode::Char(c),
+        input::KeyCode::Backspace => KeyCode::Backspace,
+        input::KeyCode::Tab => KeyCode::Tab,
+        input::KeyCode::Enter => KeyCode::Enter,
+        input::KeyCode::Escape => KeyCode::Esc,
+        input::KeyCode::PageUp | input::KeyCode::KeyPadPageUp => KeyCode::PageUp,
+        input::KeyCode::PageDown | input::KeyCode::KeyPadPageDown => KeyCode::PageDown,
+        input::KeyCode::End | input::KeyCode::KeyPadEnd => KeyCode::End,
+        input::KeyCode::Home | input::KeyCode::KeyPadHome => KeyCode::Home,
+        input::KeyCode::LeftArrow | input::KeyCode::ApplicationLeftArrow => KeyCode::Left,
+        input::KeyCode::RightArrow | input::KeyCode::ApplicationRightArrow => KeyCode::Right,
+        input::KeyCode::UpArrow | input::KeyCode::ApplicationUpArrow => KeyCode::Up,
+        input::KeyCode::DownArrow | input::KeyCode::ApplicationDownArrow => KeyCode::Down,
+        input::KeyCode::Insert => KeyCode::Insert,
+        input::KeyCode::Delete => KeyCode::Delete,
+        input::KeyCode::Function(n) => KeyCode::F(n),
+        input::KeyCode::CapsLock => KeyCode::CapsLock,
+        input::KeyCode::ScrollLock => KeyCode::ScrollLock,
+        input::KeyCode::NumLock => KeyCode::NumLock,
+        input::KeyCode::PrintScreen => KeyCode::PrintScreen,
+        input::KeyCode::Pause => KeyCode::Pause,
+        input::KeyCode::Menu => KeyCode::Menu,
+        input::KeyCode::KeyPadBegin => KeyCode::KeypadBegin,
+        input::KeyCode::Numpad0 => KeyCode::Char('0'),
+        input::KeyCode::Numpad1 => KeyCode::Char('1'),
+        input::KeyCode::Numpad2 => KeyCode::Char('2'),
+        input::KeyCode::Numpad3 => KeyCode::Char('3'),
+        input::KeyCode::Numpad4 => KeyCode::Char('4'),
+        input::KeyCode::Numpad5 => KeyCode::Char('5'),
+        input::KeyCode::Numpad6 => KeyCode::Char('6'),
+        input::KeyCode::Numpad7 => KeyCode::Char('7'),
+        input::KeyCode::Numpad8 => KeyCode::Char('8'),
+        input::KeyCode::Numpad9 => KeyCode::Char('9'),
+        input::KeyCode::Multiply => KeyCode::Char('*'),
+        input::KeyCode::Add => KeyCode::Char('+'),
+        input::KeyCode::Subtract => KeyCode::Char('-'),
+        input::KeyCode::Decimal => KeyCode::Char('.'),
+        input::KeyCode::Divide => KeyCode::Char('/'),
+        _ => return None,
+    })
+}
+
+fn to_key_modifiers(modifiers: Modifiers) -> KeyModifiers {
+    let mut key_modifiers = KeyModifiers::NONE;
+    if modifiers.contains(Modifiers::SHIFT) {
+        key_modifiers |= KeyModifiers::SHIFT;
+    }
+    if modifiers.contains(Modifiers::CTRL) {
+        key_modifiers |= KeyModifiers::CONTROL;
+    }
+    if modifiers.contains(Modifiers::ALT) {
+        key_modifiers |= KeyModifiers::ALT;
+    }
+    if modifiers.contains(Modifiers::SUPER) {
+        key_modifiers |= KeyModifiers::SUPER;
+    }
+    key_modifiers
+}
@@ -0,0 +1,200 @@
+//! Helpers to drive and inspect an [`App`] rendering to a test backend.
+//!
+//! They expect the app to use [`BackendKind::Test`](crate::BackendKind::Test) or
+//! [`BackendKind::Record`](crate::BackendKind::Record), as created by
+//! [`AppExt::new_tui_test`](crate::AppExt::new_tui_test).
+//!
+//! The real terminal is never set up or restored by these apps, even when they exit or panic,
+//! so tests can't change the state of the terminal they run in:
+//!
+//! ```rust
+//! # use ratatecs::prelude::*;
+//! # use ratatecs::testing::step;
+//! fn fail() {
+//!     panic!("failed");
+//! }
+//!
+//! let mut app = App::new_tui_test(10, 1);
+//! app.add_systems(Update, fail);
+//! let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| step(&mut app, 1)));
+//! assert!(panicked.is_err());
+//! ```
+
+use bevy_app::{App, PluginsState};
+use ratatui::{
+    backend::TestBackend,
+    buffer::{Buffer, Cell},
+    style::{Color, Style},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{export, TerminalWrapper};
+
+/// Run `frames` frames of the app, without the loop of [`App::run`] that never returns.
+///
+/// Plugins are finished before the first frame, like the runner would do.
+pub fn step(app: &mut App, frames: usize) {
+    if app.plugins_state() != PluginsState::Cleaned {
+        while app.plugins_state() == PluginsState::Adding {
+            std::thread::yield_now();
+        }
+        app.finish();
+        app.cleanup();
+    }
+
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+/// The buffer of the test backend, as of the last render.
+///
+/// # Panics
+///
+/// If the app doesn't use a test backend.
+pub fn buffer(app: &App) -> &Buffer {
+    app.world()
+        .get_non_send_resource::<TerminalWrapper<TestBackend>>()
+        .expect("the app doesn't render to a test backend")
+        .terminal
+        .backend()
+        .buffer()
+}
+
+/// The content of the test backend as plain text, one line per row.
+pub fn buffer_to_string(app: &App) -> String {
+    export::to_text(buffer(app))
+}
+
+/// The content of the test backend as plain text for snapshot tests, like with
+/// `insta::assert_snapshot!`.
+///
+/// Unlike [`buffer_to_string`], trailing spaces are removed from each line, so snapshots don't
+/// depend on invisible whitespace.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{snapshot, step};
+/// # use ratatui::widgets::Paragraph;
+/// fn hello(mut widgets: NonSendMut<WidgetsToDraw>) {
+///     widgets.push_widget(Box::new(Paragraph::new("hello")), Rect::new(1, 1, 5, 1), 0);
+/// }
+///
+/// let mut app = App::new_tui_test(8, 2);
+/// app.add_systems(Update, hello);
+/// step(&mut app, 1);
+/// assert_eq!(snapshot(&app), "\n hello");
+/// ```
+pub fn snapshot(app: &App) -> String {
+    buffer_to_string(app)
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Like [`snapshot`], with the styles of the cells as ANSI escape codes, see [`export::to_ansi`].
+pub fn snapshot_ansi(app: &App) -> String {
+    export::to_ansi(buffer(app))
+}
+
+/// The content of the test backend as runs of text with the same style, to assert how text is
+/// styled.
+///
+/// Runs are read row by row, and never span several rows. Colors left to the terminal's default
+/// are `None`, so runs compare equal to styles built from [`Style::new`], like with the
+/// [`Stylize`](ratatui::style::Stylize) shorthands.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{step, styled_snapshot};
+/// # use ratatui::widgets::Paragraph;
+/// // The counter of the `simple` example
+/// fn counter(mut widgets: NonSendMut<WidgetsToDraw>) {
+///     let text = Line::from(vec!["Value: ".into(), 0.to_string().yellow()]);
+///     widgets.push_widget(Box::new(Paragraph::new(text)), Rect::new(0, 0, 10, 1), 0);
+/// }
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.add_systems(Update, counter);
+/// step(&mut app, 1);
+/// let runs = styled_snapshot(&app);
+/// assert_eq!(
+///     runs,
+///     [
+///         ("Value: ".to_string(), Style::new()),
+///         ("0".to_string(), Style::new().yellow()),
+///         ("  ".to_string(), Style::new()),
+///     ]
+/// );
+/// ```
+pub fn styled_snapshot(app: &App) -> Vec<(String, Style)> {
+    let buffer = buffer(app);
+    let mut runs: Vec<(String, Style)> = Vec::new();
+    for y in 0..buffer.area.height {
+        let row_start = runs.len();
+        let mut skip = 0usize;
+        for x in 0..buffer.area.width {
+            let cell = &buffer[(buffer.area.x + x, buffer.area.y + y)];
+            // Cells covered by a wide character are not written, like with `export::to_text`
+            if skip > 0 || cell.skip {
+                skip = skip.saturating_sub(1);
+                continue;
+            }
+            skip = cell.symbol().width().saturating_sub(1);
+            let style = cell_style(cell);
+            match runs[row_start..].last_mut() {
+                Some((text, run_style)) if *run_style == style => {
+                    text.push_str(cell.symbol());
+                }
+                _ => runs.push((cell.symbol().to_string(), style)),
+            }
+        }
+    }
+    runs
+}
+
+/// The style of the cell, with `None` instead of [`Color::Reset`].
+fn cell_style(cell: &Cell) -> Style {
+    let color = |color| (color != Color::Reset).then_some(color);
+    Style {
+        fg: color(cell.fg),
+        bg: color(cell.bg),
+        underline_color: color(cell.underline_color),
+        add_modifier: cell.modifier,
+        ..Style::new()
+    }
+}
+
+/// Assert that the test backend displays `text` somewhere.
+#[track_caller]
+pub fn assert_buffer_contains(app: &App, text: &str) {
+    let content = buffer_to_string(app);
+    assert!(
+        content.contains(text),
+        "buffer doesn't contain {text:?}:\n{content}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        panic::{self, AssertUnwindSafe},
+        sync::atomic::Ordering,
+    };
+
+    use bevy_app::Update;
+
+    use super::*;
+    use crate::{terminal, AppExt};
+
+    #[test]
+    fn panicking_test_app_doesnt_restore_the_terminal() {
+        let mut app = App::new_tui_test(10, 1);
+        app.add_systems(Update, || panic!("failed"));
+        let panicked = panic::catch_unwind(AssertUnwindSafe(|| step(&mut app, 1)));
+        assert!(panicked.is_err());
+        assert!(terminal::enabled_modes().is_none());
+        assert_eq!(terminal::LEAVE_CALLS.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,53 @@
+//! Size strings by their width on the terminal rather than their length.
+//!
+//! Wide characters, like CJK characters and most emoji, take two columns, and combining
+//! characters, like accents, take none. These helpers measure the columns a string takes with
+//! [`unicode-width`](unicode_width), and never split a character made of several code points.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// The longest start of `s` fitting in `width` columns.
+///
+/// A wide character that would only half fit is left out, so the result can be one column
+/// narrower than `width`.
+///
+/// ```rust
+/// # use ratatecs::text::truncate_to_width;
+/// assert_eq!(truncate_to_width("hello", 3), "hel");
+/// assert_eq!(truncate_to_width("日本語", 5), "日本");
+/// // The combining accent stays with its letter
+/// assert_eq!(truncate_to_width("cafe\u{301}s", 4), "cafe\u{301}");
+/// assert_eq!(truncate_to_width("👩‍👩‍👧 family", 3), "👩‍👩‍👧 ");
+/// ```
+pub fn truncate_to_width(s: &str, width: u16) -> &str {
+    let mut remaining = usize::from(width);
+    for (index, grapheme) in s.grapheme_indices(true) {
+        let grapheme_width = grapheme.width();
+        if grapheme_width > remaining {
+            return &s[..index];
+        }
+        remaining -= grapheme_width;
+    }
+    s
+}
+
+/// `s` truncated to `width` columns like with [`truncate_to_width`], then padded with spaces to
+/// take exactly `width` columns.
+///
+/// ```rust
+/// # use unicode_width::UnicodeWidthStr;
+/// # use ratatecs::text::pad_to_width;
+/// assert_eq!(pad_to_width("ok", 4), "ok  ");
+/// assert_eq!(pad_to_width("日本語", 5), "日本 ");
+/// assert_eq!(pad_to_width("e\u{301}", 2), "e\u{301} ");
+/// assert_eq!(pad_to_width("日本語", 5).width(), 5);
+/// ```
+pub fn pad_to_width(s: &str, width: u16) -> String {
+    let truncated = truncate_to_width(s, width);
+    let padding = usize::from(width) - truncated.width();
+    let mut padded = String::with_capacity(truncated.len() + padding);
+    padded.push_str(truncated);
+    padded.extend(std::iter::repeat_n(' ', padding));
+    padded
+}
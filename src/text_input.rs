@@ -0,0 +1,174 @@
+//! An editable line of text, for the fields of form-like panels.
+//!
+//! [`TextInput`] can be used as a resource or as a component. As a resource, add the
+//! [`edit_text_input`] system to edit it with the keyboard, usually with a run condition like
+//! [`has_focus`](crate::focus::has_focus). As a component, call [`TextInput::apply`] with the
+//! pressed keys from the panel's own system.
+//!
+//! ```rust,no_run
+//! use ratatecs::prelude::*;
+//! use ratatui::widgets::{Block, Paragraph};
+//!
+//! fn name_field(mut drawer: WidgetDrawer, name: Res<TextInput>) {
+//!     let area = Rect::new(0, 0, 30, 3);
+//!     let field = Paragraph::new(name.value.clone()).block(Block::bordered().title("Name"));
+//!     drawer.push_widget(Box::new(field), area, 0);
+//!     drawer.set_cursor(area.x + 1 + name.cursor_column(), area.y + 1);
+//! }
+//!
+//! App::new()
+//!     .add_plugins(RatatEcsPlugins::new())
+//!     .init_resource::<TextInput>()
+//!     .add_systems(Update, (edit_text_input, name_field).chain())
+//!     .run();
+//! ```
+
+use bevy_ecs::{
+    component::Component,
+    event::EventReader,
+    system::{ResMut, Resource},
+};
+use crossterm::event::{KeyCode, KeyModifiers};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::input::{is_key_press, KeyPressed};
+
+/// A line of text being edited, with its cursor.
+///
+/// The cursor moves and deletes whole grapheme clusters, so characters made of several code
+/// points, like an accented letter written with a combining accent or most emoji, are edited as
+/// a single character.
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// use event::{KeyCode, KeyEvent};
+///
+/// let mut input = TextInput::with_value("cafe\u{301} 👩‍👩‍👧");
+/// input.apply(&KeyEvent::from(KeyCode::Backspace));
+/// assert_eq!(input.value, "cafe\u{301} ");
+///
+/// input.apply(&KeyEvent::from(KeyCode::Left));
+/// input.apply(&KeyEvent::from(KeyCode::Backspace));
+/// assert_eq!(input.value, "caf ");
+///
+/// input.apply(&KeyEvent::from(KeyCode::Char('é')));
+/// input.apply(&KeyEvent::from(KeyCode::Home));
+/// input.apply(&KeyEvent::from(KeyCode::Delete));
+/// assert_eq!(input.value, "afé ");
+/// assert_eq!(input.cursor, 0);
+/// ```
+#[derive(Component, Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    pub value: String,
+    /// Position of the cursor in `value`, in bytes, between two grapheme clusters.
+    ///
+    /// A cursor past the end or inside a character is moved to the end before editing.
+    pub cursor: usize,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start with `value`, with the cursor at the end.
+    pub fn with_value(value: impl Into<String>) -> Self {
+        let value = value.into();
+        Self {
+            cursor: value.len(),
+            value,
+        }
+    }
+
+    /// Edit the text with a key press: type characters, delete with Backspace and Delete, and
+    /// move the cursor with the arrows, Home and End.
+    ///
+    /// Returns whether the key was handled. Key releases, other keys, and characters typed with
+    /// Ctrl or Alt held are left to the caller, like for shortcuts.
+    pub fn apply(&mut self, key: &crossterm::event::KeyEvent) -> bool {
+        if !is_key_press(key) {
+            return false;
+        }
+        self.clamp_cursor();
+        match key.code {
+            KeyCode::Char(c)
+                if !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                self.insert(c)
+            }
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left => self.cursor = self.previous_boundary(),
+            KeyCode::Right => self.cursor = self.next_boundary(),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.value.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Insert a character at the cursor, and move the cursor after it.
+    pub fn insert(&mut self, c: char) {
+        self.clamp_cursor();
+        self.value.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    /// Delete the grapheme cluster before the cursor.
+    pub fn backspace(&mut self) {
+        self.clamp_cursor();
+        let start = self.previous_boundary();
+        self.value.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Delete the grapheme cluster after the cursor.
+    pub fn delete(&mut self) {
+        self.clamp_cursor();
+        let end = self.next_boundary();
+        self.value.drain(self.cursor..end);
+    }
+
+    /// Remove all the text.
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    /// The width of the text before the cursor on the terminal, to place the terminal cursor
+    /// with [`WidgetDrawer::set_cursor`](crate::WidgetDrawer::set_cursor).
+    pub fn cursor_column(&self) -> u16 {
+        let before = self.value.get(..self.cursor).unwrap_or(&self.value);
+        before.width().try_into().unwrap_or(u16::MAX)
+    }
+
+    fn previous_boundary(&self) -> usize {
+        self.value[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.value[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |grapheme| self.cursor + grapheme.len())
+    }
+
+    fn clamp_cursor(&mut self) {
+        if !self.value.is_char_boundary(self.cursor) {
+            self.cursor = self.value.len();
+        }
+    }
+}
+
+/// Edit the [`TextInput`] resource with the pressed keys.
+pub fn edit_text_input(mut input: ResMut<TextInput>, mut keys: EventReader<KeyPressed>) {
+    for KeyPressed(key_event) in keys.read() {
+        input.apply(key_event);
+    }
+}
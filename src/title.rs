@@ -0,0 +1,126 @@
+//! The title of the terminal window.
+
+use std::io::{self, Write};
+
+use bevy_ecs::{
+    change_detection::{DetectChanges, DetectChangesMut},
+    event::EventWriter,
+    system::{NonSendMut, ResMut, Resource},
+};
+use crossterm::{execute, terminal::SetTitle};
+use ratatui::backend::CrosstermBackend;
+
+use crate::{RenderError, TerminalWrapper};
+
+/// Save the current title on the terminal's title stack, supported by xterm and most terminals
+/// based on it. Others ignore it.
+const PUSH_TITLE: &[u8] = b"\x1b[22;0t";
+/// Restore the title saved with [`PUSH_TITLE`].
+const POP_TITLE: &[u8] = b"\x1b[23;0t";
+
+/// The title of the terminal window, written to the terminal when it changes.
+///
+/// The title the terminal had before is saved when a title is first set, and restored on exit
+/// or when the title is cleared. Only used by the crossterm backends.
+///
+/// ```rust
+/// # use std::{
+/// #     io::{self, Write},
+/// #     sync::{Arc, Mutex},
+/// # };
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::step;
+/// #[derive(Clone, Default)]
+/// struct Output(Arc<Mutex<Vec<u8>>>);
+///
+/// impl Write for Output {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         self.0.lock().unwrap().write(buf)
+///     }
+///
+///     fn flush(&mut self) -> io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// let output = Output::default();
+/// let mut app = App::new();
+/// app.add_plugins(RatatEcsPlugins::new().set(TuiPlugin::with_writer(Box::new(output.clone()))));
+/// app.world_mut().resource_mut::<TerminalTitle>().set("My app");
+/// step(&mut app, 1);
+/// let written = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+/// assert!(written.contains("\x1b]0;My app\x07"));
+///
+/// app.world_mut().send_event(AppExit::Success);
+/// step(&mut app, 1);
+/// let written = String::from_utf8(output.0.lock().unwrap().clone()).unwrap();
+/// assert!(written.ends_with("\x1b[23;0t"));
+/// ```
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct TerminalTitle {
+    title: Option<String>,
+    /// Whether the previous title was saved, to restore it.
+    saved: bool,
+}
+
+impl TerminalTitle {
+    pub fn get(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn set(&mut self, title: impl Into<String>) {
+        self.title = Some(title.into());
+    }
+
+    /// Restore the title the terminal had before.
+    pub fn clear(&mut self) {
+        self.title = None;
+    }
+}
+
+pub(crate) fn apply_title<W: Write + 'static>(
+    mut title: ResMut<TerminalTitle>,
+    mut terminal: NonSendMut<TerminalWrapper<CrosstermBackend<W>>>,
+    mut errors: EventWriter<RenderError>,
+) {
+    if !title.is_changed() {
+        return;
+    }
+    let backend = terminal.terminal.backend_mut();
+    if let Err(err) = write_title(title.bypass_change_detection(), backend) {
+        errors.send(RenderError(err));
+    }
+}
+
+pub(crate) fn restore_title_on_exit<W: Write + 'static>(
+    mut title: ResMut<TerminalTitle>,
+    mut terminal: NonSendMut<TerminalWrapper<CrosstermBackend<W>>>,
+    mut errors: EventWriter<RenderError>,
+) {
+    if let Err(err) = restore(
+        title.bypass_change_detection(),
+        terminal.terminal.backend_mut(),
+    ) {
+        errors.send(RenderError(err));
+    }
+}
+
+fn write_title(title: &mut TerminalTitle, backend: &mut impl Write) -> io::Result<()> {
+    let Some(new_title) = &title.title else {
+        return restore(title, backend);
+    };
+    if !title.saved {
+        backend.write_all(PUSH_TITLE)?;
+    }
+    execute!(backend, SetTitle(new_title))?;
+    title.saved = true;
+    Ok(())
+}
+
+fn restore(title: &mut TerminalTitle, backend: &mut impl Write) -> io::Result<()> {
+    if std::mem::take(&mut title.saved) {
+        backend.write_all(POP_TITLE)?;
+        backend.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//! Named regions of the terminal that panels render into independently.
+//!
+//! A viewport is a rect of the terminal with its own coordinates: widgets pushed through a
+//! [`ViewportDrawer`] are positioned relative to the top-left corner of the viewport, and
+//! clipped to it, so a panel like a log pane doesn't need to know where it's placed.
+//!
+//! ```rust,no_run
+//! use ratatecs::prelude::*;
+//! use ratatui::widgets::Paragraph;
+//!
+//! fn split(size: Res<TerminalSize>, mut viewports: ResMut<Viewports>) {
+//!     let [main, logs] = Layout::vertical([Constraint::Fill(1), Constraint::Length(5)])
+//!         .areas(Rect::new(0, 0, size.0, size.1));
+//!     viewports.set("main", main);
+//!     viewports.set("logs", logs);
+//! }
+//!
+//! fn logs(mut drawer: WidgetDrawer) {
+//!     let Some(mut logs) = drawer.viewport("logs") else {
+//!         return;
+//!     };
+//!     logs.clear(0);
+//!     let area = logs.area();
+//!     logs.push_widget(Box::new(Paragraph::new("started")), area, 1);
+//! }
+//!
+//! App::new()
+//!     .add_plugins(RatatEcsPlugins::new())
+//!     .add_systems(Update, split)
+//!     .add_systems(PostUpdate, logs)
+//!     .run();
+//! ```
+
+use std::collections::HashMap;
+
+use bevy_ecs::system::Resource;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    widgets::{Clear, WidgetRef},
+};
+
+use crate::{WidgetId, WidgetsToDraw};
+
+/// The areas of the named viewports, in terminal coordinates.
+///
+/// The areas aren't updated when the terminal is resized, set them again from a system reading
+/// [`TerminalSize`](crate::TerminalSize). That system can't also use a `WidgetDrawer`, which
+/// reads the viewports.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Viewports {
+    areas: HashMap<&'static str, Rect>,
+}
+
+impl Viewports {
+    /// Add the viewport, or move it if it already exists.
+    pub fn set(&mut self, name: &'static str, area: Rect) {
+        self.areas.insert(name, area);
+    }
+
+    pub fn get(&self, name: &str) -> Option<Rect> {
+        self.areas.get(name).copied()
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.areas.remove(name);
+    }
+}
+
+/// Push widgets into a viewport, as returned by
+/// [`WidgetDrawer::viewport`](crate::WidgetDrawer::viewport).
+///
+/// Areas are relative to the viewport, and clipped to it.
+pub struct ViewportDrawer<'a> {
+    widgets: &'a mut WidgetsToDraw,
+    viewport: Rect,
+}
+
+impl<'a> ViewportDrawer<'a> {
+    pub(crate) fn new(widgets: &'a mut WidgetsToDraw, viewport: Rect) -> Self {
+        Self { widgets, viewport }
+    }
+
+    /// The whole viewport, in its own coordinates.
+    pub fn area(&self) -> Rect {
+        Rect::new(0, 0, self.viewport.width, self.viewport.height)
+    }
+
+    /// Push a widget to render this frame at `area`, relative to the viewport.
+    pub fn push_widget(
+        &mut self,
+        widget: Box<dyn WidgetRef>,
+        area: Rect,
+        z_order: u32,
+    ) -> WidgetId {
+        let area = self.to_terminal(area);
+        self.widgets.push_widget(widget, area, z_order)
+    }
+
+    /// Push a closure drawing directly to the buffer, see [`WidgetsToDraw::push_fn`].
+    ///
+    /// The closure receives the area in terminal coordinates, as the buffer is shared with the
+    /// other viewports.
+    pub fn push_fn(
+        &mut self,
+        area: Rect,
+        z_order: u32,
+        draw: impl FnOnce(Rect, &mut Buffer) + 'static,
+    ) -> WidgetId {
+        let area = self.to_terminal(area);
+        self.widgets.push_fn(area, z_order, draw)
+    }
+
+    /// Clear the whole viewport, below the widgets pushed with a higher `z_order`.
+    pub fn clear(&mut self, z_order: u32) {
+        self.widgets
+            .push_widget(Box::new(Clear), self.viewport, z_order);
+    }
+
+    /// Convert an area relative to the viewport to terminal coordinates, clipped to the viewport.
+    fn to_terminal(&self, area: Rect) -> Rect {
+        Rect {
+            x: self.viewport.x.saturating_add(area.x),
+            y: self.viewport.y.saturating_add(area.y),
+            ..area
+        }
+        .intersection(self.viewport)
+    }
+}
@@ -0,0 +1,105 @@
+//! Helpers building the widgets most apps use.
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, WidgetRef},
+};
+
+/// A block with a thick border, `title` centered at the top and `instructions` centered at the
+/// bottom, like around the panels of the examples.
+///
+/// Both accept anything converting to a [`Line`], like a `&str` or a styled [`Line`]. The block
+/// can still be changed, like with [`Block::border_style`].
+///
+/// ```rust
+/// # use ratatecs::widgets::titled_block;
+/// # use ratatui::{buffer::Buffer, layout::Rect, style::{Style, Stylize}, text::Line, widgets::Widget};
+/// let block = titled_block(" Title ", Line::from(" Quit <q> ").bold());
+/// let mut buf = Buffer::empty(Rect::new(0, 0, 15, 3));
+/// block.render(buf.area, &mut buf);
+/// let mut expected = Buffer::with_lines(["┏━━━ Title ━━━┓", "┃             ┃", "┗━ Quit <q> ━━┛"]);
+/// expected.set_style(Rect::new(2, 2, 10, 1), Style::new().bold());
+/// assert_eq!(buf, expected);
+/// ```
+pub fn titled_block<'a>(
+    title: impl Into<Line<'a>>,
+    instructions: impl Into<Line<'a>>,
+) -> Block<'a> {
+    Block::bordered()
+        .title(title.into().centered())
+        .title_bottom(instructions.into().centered())
+        .border_set(border::THICK)
+}
+
+/// `block` with its border highlighted when `focused`, to show which panel has the
+/// [`Focus`](crate::focus::Focus).
+///
+/// ```rust
+/// # use ratatecs::widgets::{highlight_focused, titled_block};
+/// # use ratatui::style::{Style, Stylize};
+/// let block = titled_block(" Name ", "");
+/// assert_eq!(
+///     highlight_focused(block.clone(), true),
+///     block.clone().border_style(Style::new().yellow())
+/// );
+/// assert_eq!(highlight_focused(block.clone(), false), block);
+/// ```
+pub fn highlight_focused(block: Block<'_>, focused: bool) -> Block<'_> {
+    if focused {
+        block.border_style(Style::new().yellow())
+    } else {
+        block
+    }
+}
+
+/// Widgets rendered in order into the same area, the last one on top, to push them as a single
+/// widget instead of one push per widget.
+///
+/// Like a popup made of a [`Clear`](ratatui::widgets::Clear) and its content, which then takes
+/// up a single [`WidgetId`](crate::WidgetId) for [`WidgetsToDraw::hit_test`](crate::WidgetsToDraw::hit_test):
+///
+/// ```rust
+/// # use ratatecs::prelude::*;
+/// # use ratatecs::testing::{snapshot, step};
+/// # use ratatui::widgets::{Clear, Paragraph};
+/// fn draw(mut widgets: NonSendMut<WidgetsToDraw>) {
+///     widgets.push_widget(Box::new(Paragraph::new("background")), Rect::new(0, 0, 10, 1), 0);
+///     let popup = CompositeWidget::new()
+///         .with(Clear)
+///         .with(Paragraph::new("pop"));
+///     widgets.push_widget(Box::new(popup), Rect::new(2, 0, 5, 1), 1);
+/// }
+///
+/// let mut app = App::new_tui_test(10, 1);
+/// app.add_systems(Update, draw);
+/// step(&mut app, 1);
+/// assert_eq!(snapshot(&app), "bapop  und");
+/// ```
+#[derive(Default)]
+pub struct CompositeWidget {
+    pub widgets: Vec<Box<dyn WidgetRef>>,
+}
+
+impl CompositeWidget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render `widget` after, so over, the widgets already added.
+    pub fn with(mut self, widget: impl WidgetRef + 'static) -> Self {
+        self.widgets.push(Box::new(widget));
+        self
+    }
+}
+
+impl WidgetRef for CompositeWidget {
+    fn render_ref(&self, area: Rect, buf: &mut Buffer) {
+        for widget in &self.widgets {
+            widget.render_ref(area, buf);
+        }
+    }
+}